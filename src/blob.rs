@@ -2,6 +2,8 @@
 
 use serde::{Serialize, Serializer};
 
+use crate::registry::{type_hint, Raw};
+
 /// A Blob for PostgreSQL
 pub struct PgBlob {
     dat: Vec<u8>,
@@ -13,7 +15,7 @@ impl Serialize for PgBlob {
         S: Serializer,
     {
         let s: &[u8] = &self.dat;
-        ser.serialize_bytes(s)
+        ser.serialize_newtype_struct(type_hint!("bytea"), &Raw(s))
     }
 }
 
@@ -35,3 +37,94 @@ impl From<&str> for PgBlob {
         Self::from(b)
     }
 }
+
+/// Serializes a byte field(e.g, `Vec<u8>`) as a bytea with `#[serde(with = "...")]`.
+///
+/// Without this, a `Vec<u8>` is serialized as a sequence of numbers and written
+/// as an int2[](u8 has no PostgreSQL type; it is widened to an int2).
+///
+/// ```
+/// #[derive(serde::Serialize)]
+/// struct Row {
+///     #[serde(with = "row2pgcopy::blob::as_bytea")]
+///     data: Vec<u8>,
+/// }
+/// ```
+pub mod as_bytea {
+    use serde::Serializer;
+
+    use crate::registry::{type_hint, Raw};
+
+    pub fn serialize<T, S>(val: &T, ser: S) -> Result<S::Ok, S::Error>
+    where
+        T: AsRef<[u8]>,
+        S: Serializer,
+    {
+        ser.serialize_newtype_struct(type_hint!("bytea"), &Raw(val.as_ref()))
+    }
+}
+
+#[cfg(test)]
+mod test_blob {
+    mod as_bytea {
+        use crate::item::to_writer;
+
+        #[derive(serde::Serialize)]
+        struct Row {
+            #[serde(with = "crate::blob::as_bytea")]
+            data: Vec<u8>,
+            #[serde(with = "crate::blob::as_bytea")]
+            arr: [u8; 2],
+        }
+
+        #[test]
+        fn vec() {
+            let r = Row {
+                data: vec![0x42, 0x43, 0x44],
+                arr: [1, 2],
+            };
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &r).unwrap();
+            assert_eq!(vec![0, 0, 0, 3, 0x42, 0x43, 0x44, 0, 0, 0, 2, 1, 2], buf);
+        }
+
+        #[test]
+        fn without() {
+            #[derive(serde::Serialize)]
+            struct Row {
+                data: Vec<u8>,
+            }
+
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &Row { data: vec![0x42] }).unwrap();
+            let expected: Vec<u8> = vec![
+                0, 0, 0, 26, // size
+                0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 21, // ndim, flags, int2
+                0, 0, 0, 1, 0, 0, 0, 1, // dim, lbound
+                0, 0, 0, 2, 0, 0x42,
+            ];
+            assert_eq!(expected, buf);
+        }
+    }
+
+    mod blob {
+        use crate::blob::PgBlob;
+        use crate::item::to_writer;
+
+        #[test]
+        fn array16() {
+            #[derive(serde::Serialize)]
+            struct Row {
+                keys: Vec<PgBlob>,
+            }
+
+            let r = Row {
+                keys: vec![PgBlob::from(&[7u8; 16][..])],
+            };
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &r).unwrap();
+            assert_eq!(&[0, 0, 0, 17], &buf[12..16]); // bytea
+            assert_eq!(4 + 20 + 4 + 16, buf.len());
+        }
+    }
+}
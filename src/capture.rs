@@ -0,0 +1,253 @@
+//! A serializer capturing simple values.
+//!
+//! Used to recognise the compact forms of third-party types(e.g, the octets of
+//! an [`std::net::IpAddr`]) before writing them.
+
+use serde::ser::{Impossible, SerializeSeq, SerializeTuple};
+use serde::{Serialize, Serializer};
+
+use crate::item::Error;
+
+/// A captured value.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Captured {
+    Bool(bool),
+    Int(i128),
+    Float(f64),
+    Str(String),
+    Bytes(Vec<u8>),
+    None,
+    Seq(Vec<Captured>),
+}
+
+impl Captured {
+    /// Converts a sequence of small integers to bytes.
+    pub(crate) fn octets(&self) -> Option<Vec<u8>> {
+        match self {
+            Self::Seq(items) => items
+                .iter()
+                .map(|i| match i {
+                    Self::Int(u) => u8::try_from(*u).ok(),
+                    _ => None,
+                })
+                .collect(),
+            Self::Bytes(b) => Some(b.clone()),
+            _ => None,
+        }
+    }
+}
+
+/// Captures a value.
+pub(crate) fn capture<T>(val: &T) -> Result<Captured, Error>
+where
+    T: Serialize + ?Sized,
+{
+    val.serialize(Capture)
+}
+
+struct Capture;
+
+/// Collects the items of a sequence/tuple.
+pub(crate) struct CaptureSeq(Vec<Captured>);
+
+impl SerializeSeq for CaptureSeq {
+    type Ok = Captured;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, val: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        self.0.push(capture(val)?);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Captured::Seq(self.0))
+    }
+}
+
+impl SerializeTuple for CaptureSeq {
+    type Ok = Captured;
+    type Error = Error;
+
+    fn serialize_element<T>(&mut self, val: &T) -> Result<(), Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        SerializeSeq::serialize_element(self, val)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        SerializeSeq::end(self)
+    }
+}
+
+macro_rules! capture_int {
+    ($ity: ty, $name: ident) => {
+        fn $name(self, i: $ity) -> Result<Self::Ok, Self::Error> {
+            Ok(Captured::Int(i.into()))
+        }
+    };
+}
+
+fn unsupported(what: &str) -> Error {
+    Error::Message(format!("unable to capture {what}"))
+}
+
+impl Serializer for Capture {
+    type Ok = Captured;
+    type Error = Error;
+
+    type SerializeSeq = CaptureSeq;
+    type SerializeTuple = CaptureSeq;
+    type SerializeTupleStruct = Impossible<Captured, Error>;
+    type SerializeTupleVariant = Impossible<Captured, Error>;
+    type SerializeMap = Impossible<Captured, Error>;
+    type SerializeStruct = Impossible<Captured, Error>;
+    type SerializeStructVariant = Impossible<Captured, Error>;
+
+    fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+        Ok(Captured::Bool(v))
+    }
+
+    capture_int!(i8, serialize_i8);
+    capture_int!(i16, serialize_i16);
+    capture_int!(i32, serialize_i32);
+    capture_int!(i64, serialize_i64);
+    capture_int!(i128, serialize_i128);
+    capture_int!(u8, serialize_u8);
+    capture_int!(u16, serialize_u16);
+    capture_int!(u32, serialize_u32);
+    capture_int!(u64, serialize_u64);
+
+    fn serialize_u128(self, i: u128) -> Result<Self::Ok, Self::Error> {
+        i128::try_from(i)
+            .map(Captured::Int)
+            .map_err(|_| unsupported("a large u128"))
+    }
+
+    fn serialize_f32(self, f: f32) -> Result<Self::Ok, Self::Error> {
+        Ok(Captured::Float(f.into()))
+    }
+
+    fn serialize_f64(self, f: f64) -> Result<Self::Ok, Self::Error> {
+        Ok(Captured::Float(f))
+    }
+
+    fn serialize_char(self, c: char) -> Result<Self::Ok, Self::Error> {
+        Ok(Captured::Str(c.into()))
+    }
+
+    fn serialize_str(self, s: &str) -> Result<Self::Ok, Self::Error> {
+        Ok(Captured::Str(s.into()))
+    }
+
+    fn serialize_bytes(self, b: &[u8]) -> Result<Self::Ok, Self::Error> {
+        Ok(Captured::Bytes(b.into()))
+    }
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Captured::None)
+    }
+
+    fn serialize_some<T>(self, t: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        t.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Ok(Captured::None)
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(Captured::None)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _vix: u32,
+        var: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(Captured::Str(var.into()))
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        _name: &'static str,
+        val: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        val.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _vix: u32,
+        _var: &'static str,
+        _val: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        Err(unsupported("an enum"))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Ok(CaptureSeq(Vec::with_capacity(len.unwrap_or(0))))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Ok(CaptureSeq(Vec::with_capacity(len)))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Err(unsupported("a tuple struct"))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _vix: u32,
+        _var: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Err(unsupported("an enum"))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Err(unsupported("a map"))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Err(unsupported("a struct"))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _vix: u32,
+        _var: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Err(unsupported("an enum"))
+    }
+
+    fn is_human_readable(&self) -> bool {
+        false
+    }
+}
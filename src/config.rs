@@ -0,0 +1,228 @@
+//! Options for the serializer.
+
+use std::borrow::Cow;
+
+#[cfg(feature = "encoding_rs")]
+use crate::encoding::TextEncoding;
+
+use crate::registry::TypeRegistry;
+
+/// The maximum size of a field accepted by PostgreSQL(1 GB).
+pub const MAX_FIELD_LEN_DEFAULT: usize = 1 << 30;
+
+/// How NUL characters in text values are handled.
+///
+/// PostgreSQL rejects NUL characters in text columns.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NulPolicy {
+    /// Rejects a text value containing NUL characters.
+    #[default]
+    Error,
+
+    /// Removes NUL characters.
+    Strip,
+
+    /// Replaces NUL characters with the char.
+    Replace(char),
+}
+
+/// How maps(e.g, a [`std::collections::HashMap`] field) are written.
+///
+/// The outermost map is always a row(e.g, a struct using `#[serde(flatten)]`);
+/// the policy applies to nested maps. Maps wrapped by [`crate::hstore::PgHstore`]
+/// are always hstores.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MapPolicy {
+    /// Rejects a map.
+    #[default]
+    Error,
+
+    /// Writes a map as an hstore(string keys and values only).
+    Hstore,
+
+    /// Writes a map as a jsonb(keys and values serialized by serde_json).
+    #[cfg(feature = "serde_json")]
+    Jsonb,
+}
+
+/// How enums are written.
+///
+/// Variants carrying data are written as jsonb only; the other policies reject them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EnumPolicy {
+    /// Writes the name of a unit variant as a label of a PostgreSQL enum.
+    ///
+    /// The OID is the one of the enum type if registered(the serde name of the
+    /// enum), else the one of a text.
+    Label(LabelCase),
+
+    /// Writes the index of a unit variant as an int2.
+    Int2,
+
+    /// Writes the index of a unit variant as an int4.
+    Int4,
+
+    /// Writes a variant as a jsonb(a string for a unit variant, else an object
+    /// tagged by the name, e.g, {"Circle":{"r":1.0}}).
+    #[cfg(feature = "serde_json")]
+    Jsonb,
+}
+
+impl Default for EnumPolicy {
+    fn default() -> Self {
+        Self::Label(LabelCase::default())
+    }
+}
+
+/// The case of enum labels converted from the names of variants(PascalCase).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LabelCase {
+    /// Keeps the names as is.
+    #[default]
+    AsIs,
+
+    /// "lowercase"
+    Lower,
+
+    /// "UPPERCASE"
+    Upper,
+
+    /// "snake_case"
+    Snake,
+
+    /// "SCREAMING_SNAKE_CASE"
+    ScreamingSnake,
+
+    /// "kebab-case"
+    Kebab,
+
+    /// "camelCase"
+    Camel,
+}
+
+impl LabelCase {
+    /// Converts the name of a variant to a label.
+    pub fn apply<'a>(&self, var: &'a str) -> Cow<'a, str> {
+        match self {
+            Self::AsIs => Cow::Borrowed(var),
+            Self::Lower => Cow::Owned(var.to_lowercase()),
+            Self::Upper => Cow::Owned(var.to_uppercase()),
+            Self::Snake => Cow::Owned(separate(var, '_').to_lowercase()),
+            Self::ScreamingSnake => Cow::Owned(separate(var, '_').to_uppercase()),
+            Self::Kebab => Cow::Owned(separate(var, '-').to_lowercase()),
+            Self::Camel => {
+                let mut chars = var.chars();
+                Cow::Owned(match chars.next() {
+                    Some(c) => c.to_lowercase().chain(chars).collect(),
+                    None => String::new(),
+                })
+            }
+        }
+    }
+}
+
+/// Inserts the separator before each uppercase character except the first one.
+fn separate(var: &str, sep: char) -> String {
+    let mut s: String = String::with_capacity(var.len() + 4);
+    for (i, c) in var.chars().enumerate() {
+        if 0 < i && c.is_uppercase() {
+            s.push(sep);
+        }
+        s.push(c);
+    }
+    s
+}
+
+/// Options for [`crate::item::to_writer_with_config`].
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub(crate) max_field_len: usize,
+    pub(crate) max_row_len: Option<usize>,
+    pub(crate) nul_policy: NulPolicy,
+    pub(crate) human_readable: bool,
+    pub(crate) map_policy: MapPolicy,
+    pub(crate) enum_policy: EnumPolicy,
+    pub(crate) registry: TypeRegistry,
+
+    #[cfg(feature = "encoding_rs")]
+    pub(crate) encoding: Option<TextEncoding>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            max_field_len: MAX_FIELD_LEN_DEFAULT,
+            max_row_len: None,
+            nul_policy: NulPolicy::default(),
+            human_readable: false,
+            map_policy: MapPolicy::default(),
+            enum_policy: EnumPolicy::default(),
+            registry: TypeRegistry::default(),
+
+            #[cfg(feature = "encoding_rs")]
+            encoding: None,
+        }
+    }
+}
+
+impl Config {
+    /// Sets the maximum size of a field in bytes(excluding its size prefix).
+    ///
+    /// Values larger than [`i32::MAX`] are capped since a size prefix is an i32.
+    pub fn with_max_field_len(mut self, max: usize) -> Self {
+        self.max_field_len = max;
+        self
+    }
+
+    /// Sets the maximum size of a row in bytes(size prefixes included).
+    ///
+    /// Rows are unlimited by default.
+    pub fn with_max_row_len(mut self, max: usize) -> Self {
+        self.max_row_len = Some(max);
+        self
+    }
+
+    /// Sets how NUL characters in strs/chars are handled.
+    ///
+    /// Bytes(e.g, [`crate::blob::PgBlob`]) are written as is.
+    pub fn with_nul_policy(mut self, policy: NulPolicy) -> Self {
+        self.nul_policy = policy;
+        self
+    }
+
+    /// Sets the value of [`serde::Serializer::is_human_readable`](false by default).
+    ///
+    /// Types like `uuid::Uuid` and [`std::net::IpAddr`] emit strings if true,
+    /// and their compact(binary) forms if false.
+    pub fn with_human_readable(mut self, human_readable: bool) -> Self {
+        self.human_readable = human_readable;
+        self
+    }
+
+    /// Sets how nested maps are written(rejected by default).
+    pub fn with_map_policy(mut self, policy: MapPolicy) -> Self {
+        self.map_policy = policy;
+        self
+    }
+
+    /// Sets how enums are written(labels by default).
+    pub fn with_enum_policy(mut self, policy: EnumPolicy) -> Self {
+        self.enum_policy = policy;
+        self
+    }
+
+    /// Sets the OIDs of user-defined types embedded in composite values.
+    pub fn with_registry(mut self, registry: TypeRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Sets the encoding of strs/chars(UTF-8 by default).
+    ///
+    /// The [`NulPolicy`] is applied before the conversion.
+    #[cfg(feature = "encoding_rs")]
+    pub fn with_encoding(mut self, enc: TextEncoding) -> Self {
+        self.encoding = Some(enc);
+        self
+    }
+}
@@ -0,0 +1,165 @@
+//! Client encoding conversion for text values.
+//!
+//! Text values are converted from UTF-8 using the WHATWG encodings of
+//! [`encoding_rs`], except ISO-8859-1. WHATWG treats labels like "latin1" as
+//! windows-1252, which differs from PostgreSQL's LATIN1 in 0x80..=0x9F, so these
+//! labels resolve to a real ISO-8859-1 encoder instead.
+
+use std::borrow::Cow;
+
+use encoding_rs::{Encoder, EncoderResult, Encoding};
+
+/// How characters unmappable to the target encoding are handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnmappablePolicy {
+    /// Rejects a text value containing unmappable characters.
+    #[default]
+    Error,
+
+    /// Replaces an unmappable character with the byte(e.g, b'?').
+    Replace(u8),
+}
+
+/// Labels of ISO-8859-1(PostgreSQL's LATIN1).
+const LATIN1_LABELS: &[&str] = &[
+    "latin1",
+    "l1",
+    "iso-8859-1",
+    "iso8859-1",
+    "iso88591",
+    "iso_8859-1",
+    "iso_8859-1:1987",
+    "iso-ir-100",
+    "csisolatin1",
+    "ibm819",
+    "cp819",
+];
+
+/// A target encoding for text values.
+#[derive(Debug, Clone, Copy)]
+pub struct TextEncoding {
+    /// None means ISO-8859-1, which is not a WHATWG encoding.
+    enc: Option<&'static Encoding>,
+    policy: UnmappablePolicy,
+}
+
+impl TextEncoding {
+    /// Creates a [`TextEncoding`] from an [`Encoding`](e.g, [`encoding_rs::SHIFT_JIS`]).
+    pub fn new(enc: &'static Encoding, policy: UnmappablePolicy) -> Self {
+        Self {
+            enc: Some(enc.output_encoding()),
+            policy,
+        }
+    }
+
+    /// Creates an ISO-8859-1 [`TextEncoding`], which maps U+0000..=U+00FF to the same byte.
+    pub fn latin1(policy: UnmappablePolicy) -> Self {
+        Self { enc: None, policy }
+    }
+
+    /// Creates a [`TextEncoding`] from a label(e.g, "shift_jis", "euc-jp").
+    ///
+    /// The labels of ISO-8859-1(e.g, "latin1", "iso-8859-1", "l1") create [`TextEncoding::latin1`].
+    pub fn from_label(label: &str, policy: UnmappablePolicy) -> Option<Self> {
+        let trimmed: &str = label.trim_matches(|c: char| c.is_ascii_whitespace());
+        if LATIN1_LABELS
+            .iter()
+            .any(|l| l.eq_ignore_ascii_case(trimmed))
+        {
+            return Some(Self::latin1(policy));
+        }
+        Encoding::for_label(label.as_bytes()).map(|enc| Self::new(enc, policy))
+    }
+
+    /// Converts a UTF-8 str to the target encoding.
+    ///
+    /// Returns the first unmappable character on error.
+    pub(crate) fn encode<'s>(&self, s: &'s str) -> Result<Cow<'s, [u8]>, char> {
+        let enc: &'static Encoding = match self.enc {
+            None => return self.encode_latin1(s),
+            Some(enc) => enc,
+        };
+        if enc == encoding_rs::UTF_8 || (s.is_ascii() && enc.is_ascii_compatible()) {
+            return Ok(Cow::Borrowed(s.as_bytes()));
+        }
+        let mut encoder: Encoder = enc.new_encoder();
+        let mut out: Vec<u8> = Vec::with_capacity(s.len());
+        let mut src: &str = s;
+        loop {
+            let (r, read) =
+                encoder.encode_from_utf8_to_vec_without_replacement(src, &mut out, true);
+            src = &src[read..];
+            match r {
+                EncoderResult::InputEmpty => return Ok(Cow::Owned(out)),
+                EncoderResult::OutputFull => out.reserve(src.len().max(8)),
+                EncoderResult::Unmappable(c) => match self.policy {
+                    UnmappablePolicy::Error => return Err(c),
+                    UnmappablePolicy::Replace(b) => {
+                        out.reserve(1);
+                        out.push(b)
+                    }
+                },
+            }
+        }
+    }
+
+    fn encode_latin1<'s>(&self, s: &'s str) -> Result<Cow<'s, [u8]>, char> {
+        if s.is_ascii() {
+            return Ok(Cow::Borrowed(s.as_bytes()));
+        }
+        s.chars()
+            .map(|c| match (u8::try_from(c), self.policy) {
+                (Ok(b), _) => Ok(b),
+                (Err(_), UnmappablePolicy::Replace(b)) => Ok(b),
+                (Err(_), UnmappablePolicy::Error) => Err(c),
+            })
+            .collect::<Result<Vec<u8>, char>>()
+            .map(Cow::Owned)
+    }
+}
+
+#[cfg(test)]
+mod test_encoding {
+    use crate::encoding::{TextEncoding, UnmappablePolicy};
+
+    #[test]
+    fn ascii() {
+        let e = TextEncoding::from_label("shift_jis", UnmappablePolicy::Error).unwrap();
+        assert_eq!(b"fuji", e.encode("fuji").unwrap().as_ref());
+    }
+
+    #[test]
+    fn sjis() {
+        let e = TextEncoding::new(encoding_rs::SHIFT_JIS, UnmappablePolicy::Error);
+        assert_eq!(b"\x95\x78\x8e\x6d", e.encode("富士").unwrap().as_ref());
+    }
+
+    #[test]
+    fn latin() {
+        let e = TextEncoding::from_label("latin1", UnmappablePolicy::Error).unwrap();
+        assert_eq!(b"caf\xe9", e.encode("café").unwrap().as_ref());
+        assert_eq!(Err('€'), e.encode("5€"));
+        assert_eq!(b"\x85", e.encode("\u{85}").unwrap().as_ref());
+
+        let e = TextEncoding::from_label(" ISO-8859-1", UnmappablePolicy::Error).unwrap();
+        assert_eq!(Err('€'), e.encode("€"));
+    }
+
+    #[test]
+    fn windows1252() {
+        let e = TextEncoding::from_label("windows-1252", UnmappablePolicy::Error).unwrap();
+        assert_eq!(b"\x80", e.encode("€").unwrap().as_ref());
+    }
+
+    #[test]
+    fn unmappable() {
+        let e = TextEncoding::from_label("latin1", UnmappablePolicy::Error).unwrap();
+        assert_eq!(Err('富'), e.encode("富士山"));
+    }
+
+    #[test]
+    fn replace() {
+        let e = TextEncoding::from_label("latin1", UnmappablePolicy::Replace(b'?')).unwrap();
+        assert_eq!(b"?? caf\xe9", e.encode("富士 café").unwrap().as_ref());
+    }
+}
@@ -0,0 +1,316 @@
+//! Geometric structs(point, line, lseg, box, path, polygon, circle).
+
+use serde::ser::Error;
+use serde::{Serialize, Serializer};
+
+use crate::registry::{type_hint, Raw};
+
+fn put(buf: &mut Vec<u8>, fs: &[f64]) {
+    for f in fs {
+        buf.extend_from_slice(&f.to_be_bytes());
+    }
+}
+
+/// Extends a buf by the number of points and the points.
+fn put_points(buf: &mut Vec<u8>, pts: &[PgPoint]) -> Result<(), String> {
+    let npts: i32 = i32::try_from(pts.len())
+        .ok()
+        .filter(|n| 0 < *n)
+        .ok_or_else(|| format!("invalid number of points: {}", pts.len()))?;
+    buf.extend_from_slice(&npts.to_be_bytes());
+    for p in pts {
+        put(buf, &[p.x, p.y]);
+    }
+    Ok(())
+}
+
+/// A point(x, y)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PgPoint {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl From<(f64, f64)> for PgPoint {
+    fn from(p: (f64, f64)) -> Self {
+        Self { x: p.0, y: p.1 }
+    }
+}
+
+impl Serialize for PgPoint {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut buf: Vec<u8> = Vec::with_capacity(16);
+        put(&mut buf, &[self.x, self.y]);
+        ser.serialize_newtype_struct(type_hint!("point"), &Raw(&buf))
+    }
+}
+
+/// An infinite line(Ax + By + C = 0)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PgLine {
+    pub a: f64,
+    pub b: f64,
+    pub c: f64,
+}
+
+impl From<(f64, f64, f64)> for PgLine {
+    fn from(l: (f64, f64, f64)) -> Self {
+        Self {
+            a: l.0,
+            b: l.1,
+            c: l.2,
+        }
+    }
+}
+
+impl Serialize for PgLine {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if 0.0 == self.a && 0.0 == self.b {
+            return Err(S::Error::custom(
+                "invalid line: A and B cannot both be zero",
+            ));
+        }
+        let mut buf: Vec<u8> = Vec::with_capacity(24);
+        put(&mut buf, &[self.a, self.b, self.c]);
+        ser.serialize_newtype_struct(type_hint!("line"), &Raw(&buf))
+    }
+}
+
+/// A line segment
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PgLseg(pub PgPoint, pub PgPoint);
+
+impl From<((f64, f64), (f64, f64))> for PgLseg {
+    fn from(l: ((f64, f64), (f64, f64))) -> Self {
+        Self(l.0.into(), l.1.into())
+    }
+}
+
+impl Serialize for PgLseg {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut buf: Vec<u8> = Vec::with_capacity(32);
+        put(&mut buf, &[self.0.x, self.0.y, self.1.x, self.1.y]);
+        ser.serialize_newtype_struct(type_hint!("lseg"), &Raw(&buf))
+    }
+}
+
+/// A box(any two opposite corners)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PgBox(pub PgPoint, pub PgPoint);
+
+impl From<((f64, f64), (f64, f64))> for PgBox {
+    fn from(b: ((f64, f64), (f64, f64))) -> Self {
+        Self(b.0.into(), b.1.into())
+    }
+}
+
+impl Serialize for PgBox {
+    /// Writes the upper right corner, then the lower left corner.
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let (p, q) = (self.0, self.1);
+        let mut buf: Vec<u8> = Vec::with_capacity(32);
+        put(
+            &mut buf,
+            &[p.x.max(q.x), p.y.max(q.y), p.x.min(q.x), p.y.min(q.y)],
+        );
+        ser.serialize_newtype_struct(type_hint!("box"), &Raw(&buf))
+    }
+}
+
+/// A path(open or closed) of one or more points
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PgPath {
+    pub closed: bool,
+    pub points: Vec<PgPoint>,
+}
+
+impl PgPath {
+    pub fn open(points: Vec<PgPoint>) -> Self {
+        Self {
+            closed: false,
+            points,
+        }
+    }
+
+    pub fn closed(points: Vec<PgPoint>) -> Self {
+        Self {
+            closed: true,
+            points,
+        }
+    }
+}
+
+/// Creates an open path.
+impl From<&[(f64, f64)]> for PgPath {
+    fn from(s: &[(f64, f64)]) -> Self {
+        Self::open(s.iter().map(|p| PgPoint::from(*p)).collect())
+    }
+}
+
+impl Serialize for PgPath {
+    /// Writes the closed flag, the number of points and the points.
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut buf: Vec<u8> = Vec::with_capacity(5 + 16 * self.points.len());
+        buf.push(self.closed.into());
+        put_points(&mut buf, &self.points).map_err(S::Error::custom)?;
+        ser.serialize_newtype_struct(type_hint!("path"), &Raw(&buf))
+    }
+}
+
+/// A polygon(an implicitly closed path) of one or more points
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PgPolygon(pub Vec<PgPoint>);
+
+impl From<Vec<PgPoint>> for PgPolygon {
+    fn from(v: Vec<PgPoint>) -> Self {
+        Self(v)
+    }
+}
+
+impl From<&[(f64, f64)]> for PgPolygon {
+    fn from(s: &[(f64, f64)]) -> Self {
+        Self(s.iter().map(|p| PgPoint::from(*p)).collect())
+    }
+}
+
+impl Serialize for PgPolygon {
+    /// Writes the number of points and the points.
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut buf: Vec<u8> = Vec::with_capacity(4 + 16 * self.0.len());
+        put_points(&mut buf, &self.0).map_err(S::Error::custom)?;
+        ser.serialize_newtype_struct(type_hint!("polygon"), &Raw(&buf))
+    }
+}
+
+/// A circle(center and radius)
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PgCircle {
+    pub center: PgPoint,
+    pub radius: f64,
+}
+
+impl From<((f64, f64), f64)> for PgCircle {
+    fn from(c: ((f64, f64), f64)) -> Self {
+        Self {
+            center: c.0.into(),
+            radius: c.1,
+        }
+    }
+}
+
+impl Serialize for PgCircle {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if self.radius < 0.0 {
+            return Err(S::Error::custom(format!(
+                "invalid radius of a circle: {}",
+                self.radius
+            )));
+        }
+        let mut buf: Vec<u8> = Vec::with_capacity(24);
+        put(&mut buf, &[self.center.x, self.center.y, self.radius]);
+        ser.serialize_newtype_struct(type_hint!("circle"), &Raw(&buf))
+    }
+}
+
+#[cfg(test)]
+mod test_geometric {
+    use crate::geometric::{PgBox, PgCircle, PgLine, PgLseg, PgPath, PgPoint, PgPolygon};
+    use crate::item::to_writer;
+
+    fn encode<T>(val: &T) -> Vec<u8>
+    where
+        T: serde::Serialize,
+    {
+        let mut buf: Vec<u8> = vec![];
+        to_writer(&mut buf, val).unwrap();
+        buf
+    }
+
+    fn floats(size: i32, fs: &[f64]) -> Vec<u8> {
+        let mut v: Vec<u8> = size.to_be_bytes().to_vec();
+        for f in fs {
+            v.extend_from_slice(&f.to_be_bytes());
+        }
+        v
+    }
+
+    #[test]
+    fn point() {
+        let p: PgPoint = (1.0, 2.0).into();
+        assert_eq!(floats(16, &[1.0, 2.0]), encode(&p));
+    }
+
+    #[test]
+    fn line() {
+        let l: PgLine = (1.0, -1.0, 0.0).into();
+        assert_eq!(floats(24, &[1.0, -1.0, 0.0]), encode(&l));
+        let mut buf: Vec<u8> = vec![];
+        assert!(to_writer(&mut buf, &PgLine::from((0.0, 0.0, 1.0))).is_err());
+    }
+
+    #[test]
+    fn lseg() {
+        let l: PgLseg = ((1.0, 2.0), (3.0, 4.0)).into();
+        assert_eq!(floats(32, &[1.0, 2.0, 3.0, 4.0]), encode(&l));
+    }
+
+    #[test]
+    fn boxed() {
+        let b: PgBox = ((3.0, 0.0), (1.0, 2.0)).into();
+        assert_eq!(floats(32, &[3.0, 2.0, 1.0, 0.0]), encode(&b));
+    }
+
+    #[test]
+    fn path() {
+        let pts: &[(f64, f64)] = &[(0.0, 0.0), (1.0, 1.0)];
+        let open: PgPath = pts.into();
+        let mut expected: Vec<u8> = vec![0, 0, 0, 37, 0, 0, 0, 0, 2];
+        expected.extend_from_slice(&floats(0, &[0.0, 0.0, 1.0, 1.0])[4..]);
+        assert_eq!(expected, encode(&open));
+
+        let closed: PgPath = PgPath::closed(open.points);
+        expected[4] = 1;
+        assert_eq!(expected, encode(&closed));
+
+        let mut buf: Vec<u8> = vec![];
+        assert!(to_writer(&mut buf, &PgPath::default()).is_err());
+    }
+
+    #[test]
+    fn polygon() {
+        let pts: &[(f64, f64)] = &[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0)];
+        let p: PgPolygon = pts.into();
+        let mut expected: Vec<u8> = vec![0, 0, 0, 52, 0, 0, 0, 3];
+        expected.extend_from_slice(&floats(0, &[0.0, 0.0, 1.0, 0.0, 1.0, 1.0])[4..]);
+        assert_eq!(expected, encode(&p));
+    }
+
+    #[test]
+    fn circle() {
+        let c: PgCircle = ((1.0, 2.0), 3.0).into();
+        assert_eq!(floats(24, &[1.0, 2.0, 3.0]), encode(&c));
+        let mut buf: Vec<u8> = vec![];
+        assert!(to_writer(&mut buf, &PgCircle::from(((0.0, 0.0), -1.0))).is_err());
+    }
+}
@@ -0,0 +1,108 @@
+//! Struct for hstore(a map of texts).
+//!
+//! The OID of hstore is database-specific; register it to use hstores in
+//! composite values or arrays(see [`crate::registry::TypeRegistry`]).
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Serialize, Serializer};
+
+use crate::registry::type_hint;
+
+/// An hstore(keys and nullable values)
+///
+/// Keys and values are written as texts(see [`crate::config::Config`]).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PgHstore(pub BTreeMap<String, Option<String>>);
+
+impl From<BTreeMap<String, Option<String>>> for PgHstore {
+    fn from(m: BTreeMap<String, Option<String>>) -> Self {
+        Self(m)
+    }
+}
+
+impl From<HashMap<String, Option<String>>> for PgHstore {
+    fn from(m: HashMap<String, Option<String>>) -> Self {
+        Self(m.into_iter().collect())
+    }
+}
+
+impl From<BTreeMap<String, String>> for PgHstore {
+    fn from(m: BTreeMap<String, String>) -> Self {
+        Self(m.into_iter().map(|(k, v)| (k, Some(v))).collect())
+    }
+}
+
+impl From<HashMap<String, String>> for PgHstore {
+    fn from(m: HashMap<String, String>) -> Self {
+        Self(m.into_iter().map(|(k, v)| (k, Some(v))).collect())
+    }
+}
+
+impl Serialize for PgHstore {
+    /// Writes the number of pairs, then the keys and values(size, text).
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ser.serialize_newtype_struct(type_hint!("hstore"), &self.0)
+    }
+}
+
+#[cfg(test)]
+mod test_hstore {
+    use std::collections::{BTreeMap, HashMap};
+
+    use crate::config::{Config, NulPolicy};
+    use crate::hstore::PgHstore;
+    use crate::item::{to_writer, to_writer_with_config};
+
+    #[test]
+    fn hstore() {
+        let m: BTreeMap<String, Option<String>> =
+            BTreeMap::from([("a".into(), Some("1".into())), ("b".into(), None)]);
+        let mut buf: Vec<u8> = vec![];
+        to_writer(&mut buf, &PgHstore::from(m)).unwrap();
+        let expected: Vec<u8> = vec![
+            0, 0, 0, 23, // size
+            0, 0, 0, 2, // pairs
+            0, 0, 0, 1, b'a', 0, 0, 0, 1, b'1', // a => 1
+            0, 0, 0, 1, b'b', 0xff, 0xff, 0xff, 0xff, // b => NULL
+        ];
+        assert_eq!(expected, buf);
+    }
+
+    #[test]
+    fn empty() {
+        let h: PgHstore = PgHstore::from(HashMap::<String, String>::new());
+        let mut buf: Vec<u8> = vec![];
+        to_writer(&mut buf, &h).unwrap();
+        assert_eq!(vec![0, 0, 0, 4, 0, 0, 0, 0], buf);
+    }
+
+    #[test]
+    fn nul() {
+        let m: BTreeMap<String, String> = BTreeMap::from([("a".into(), "x\0".into())]);
+        let h: PgHstore = PgHstore::from(m);
+        let mut buf: Vec<u8> = vec![];
+        assert!(to_writer(&mut buf, &h).is_err());
+        let cfg = Config::default().with_nul_policy(NulPolicy::Strip);
+        buf.clear();
+        to_writer_with_config(&mut buf, &h, &cfg).unwrap();
+        assert_eq!(&[0, 0, 0, 1, b'x'], &buf[buf.len() - 5..]);
+    }
+
+    #[test]
+    fn plain_map() {
+        #[derive(serde::Serialize)]
+        struct Row {
+            attrs: HashMap<String, i32>,
+        }
+
+        let r = Row {
+            attrs: HashMap::new(),
+        };
+        let mut buf: Vec<u8> = vec![];
+        assert!(to_writer(&mut buf, &r).is_err());
+    }
+}
@@ -3,7 +3,9 @@
 use core::fmt;
 use core::fmt::Display;
 
+use std::borrow::Cow;
 use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
 
 use serde::ser;
 
@@ -13,6 +15,14 @@ use serde::ser::{SerializeTuple, SerializeTupleStruct, SerializeTupleVariant};
 
 use serde::{Serialize, Serializer};
 
+use crate::capture::{capture, Captured};
+use crate::config::{Config, EnumPolicy, MapPolicy, NulPolicy};
+use crate::net::{PgCidr, PgInet};
+use crate::registry::{is_builtin, is_text, type_hint, Raw, RECORD_OID, TYPE_HINT_PREFIX};
+
+#[cfg(feature = "serde_json")]
+use serde_json::{Map, Value};
+
 /// Writes a number of columns to a wtr.
 ///
 /// ## Arguments
@@ -33,6 +43,9 @@ pub trait PgNum {
     fn to_buf(&self, buf: &mut Vec<u8>);
 
     /// A type number. e.g, i16 -> 0x15, i32 -> 0x17, f32 -> 0x02bc, ...
+    ///
+    /// The OID of the array type is resolved from it through the
+    /// [`crate::registry::TypeRegistry`](e.g, 0x17 -> int4[]).
     fn type_num() -> u32;
 
     /// The size of a number type. e.g, i16 -> 2, f64 -> 8, ...
@@ -98,44 +111,59 @@ where
     {
         let esz: usize = T::size();
         let ecnt: usize = self.0.len();
-        let sz: usize = match ecnt {
-            0 => 12,
-            _ => 4 * 5 + (4 + esz) * ecnt,
+        let cnt: i32 = i32::try_from(ecnt)
+            .map_err(|_| ser::Error::custom(format!("too many array items: {ecnt}")))?;
+        let isz: i32 = i32::try_from(esz)
+            .map_err(|_| ser::Error::custom(format!("too large array item: {esz}")))?;
+
+        let mut buf: Vec<u8> = Vec::with_capacity(20 + (4 + esz) * ecnt);
+        let ndim: i32 = match ecnt {
+            0 => 0,
+            _ => 1,
         };
-
-        let mut buf: Vec<u8> = Vec::with_capacity(sz);
-
-        if 0 == ecnt {
-            buf.extend_from_slice(&[0, 0, 0, 0]);
-            buf.extend_from_slice(&[0, 0, 0, 0]);
-            let typ_num: u32 = T::type_num();
-            let b: [u8; 4] = typ_num.to_be_bytes();
-            buf.extend_from_slice(&b);
-        } else {
+        buf.extend_from_slice(&ndim.to_be_bytes());
+        buf.extend_from_slice(&[0, 0, 0, 0]);
+        buf.extend_from_slice(&T::type_num().to_be_bytes());
+        if 0 < ecnt {
+            buf.extend_from_slice(&cnt.to_be_bytes());
             buf.extend_from_slice(&[0, 0, 0, 1]);
-            buf.extend_from_slice(&[0, 0, 0, 0]);
-            let typ_num: u32 = T::type_num();
-            let b: [u8; 4] = typ_num.to_be_bytes();
-            buf.extend_from_slice(&b);
+        }
+        for item in &self.0 {
+            buf.extend_from_slice(&isz.to_be_bytes());
+            item.to_buf(&mut buf);
+        }
+        ser.serialize_newtype_struct(type_hint!("anyarray"), &Raw(&buf))
+    }
+}
 
-            let isz: i32 = ecnt as i32;
-            let bsz: [u8; 4] = isz.to_be_bytes();
-            buf.extend_from_slice(&bsz);
+/// An array of postgresql texts(text[])
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PgTextArray(pub Vec<Option<String>>);
 
-            buf.extend_from_slice(&[0, 0, 0, 1]);
-        }
+impl From<Vec<Option<String>>> for PgTextArray {
+    fn from(v: Vec<Option<String>>) -> Self {
+        Self(v)
+    }
+}
 
-        let items: &[T] = &self.0;
-        let isz: i32 = esz as i32;
-        let ib: [u8; 4] = isz.to_be_bytes();
+impl From<Vec<String>> for PgTextArray {
+    fn from(v: Vec<String>) -> Self {
+        Self(v.into_iter().map(Some).collect())
+    }
+}
 
-        for item in items {
-            buf.extend_from_slice(&ib);
-            item.to_buf(&mut buf);
-        }
+impl From<Vec<&str>> for PgTextArray {
+    fn from(v: Vec<&str>) -> Self {
+        Self(v.into_iter().map(|s| Some(s.into())).collect())
+    }
+}
 
-        let s: &[u8] = &buf;
-        ser.serialize_bytes(s)
+impl Serialize for PgTextArray {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ser.serialize_newtype_struct(type_hint!("_text"), &self.0)
     }
 }
 
@@ -149,19 +177,215 @@ where
     W: io::Write,
     T: Serialize,
 {
-    let mut ser = Ser { wtr };
+    let cfg = Config::default();
+    to_writer_with_config(wtr, val, &cfg)
+}
+
+/// Writes a val to a wtr using the options.
+///
+/// ## Arguments
+/// - wtr: The target writer which implements [`io::Write`].
+/// - val: The value to write which implenents [`Serialize`].
+/// - cfg: The serializer options.
+pub fn to_writer_with_config<W, T>(wtr: W, val: &T, cfg: &Config) -> Result<(), Error>
+where
+    W: io::Write,
+    T: Serialize,
+{
+    let mut ser = Ser {
+        wtr,
+        cfg,
+        path: vec![],
+        row_len: 0,
+        frames: vec![],
+        hint: None,
+    };
     val.serialize(&mut ser)
 }
 
-struct Ser<W> {
+/// Computes the number of bytes [`to_writer`] would write for a val.
+///
+/// The val is serialized into a counting sink; the column count written by
+/// [`write_col_cnt`] is not included.
+pub fn encoded_len<T>(val: &T) -> Result<usize, Error>
+where
+    T: Serialize,
+{
+    let cfg = Config::default();
+    encoded_len_with_config(val, &cfg)
+}
+
+/// Computes the number of bytes [`to_writer_with_config`] would write for a val.
+pub fn encoded_len_with_config<T>(val: &T, cfg: &Config) -> Result<usize, Error>
+where
+    T: Serialize,
+{
+    let mut cnt = ByteCounter { cnt: 0 };
+    to_writer_with_config(&mut cnt, val, cfg)?;
+    Ok(cnt.cnt)
+}
+
+struct ByteCounter {
+    cnt: usize,
+}
+
+impl io::Write for ByteCounter {
+    fn write(&mut self, buf: &[u8]) -> Result<usize, io::Error> {
+        self.cnt += buf.len();
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<(), io::Error> {
+        Ok(())
+    }
+}
+
+struct Ser<'c, W> {
     wtr: W,
+    cfg: &'c Config,
+
+    /// Names of the fields being serialized(outermost first).
+    path: Vec<Cow<'static, str>>,
+
+    /// Bytes written for the current row so far.
+    row_len: usize,
+
+    /// Structs being serialized(outermost first).
+    frames: Vec<Frame>,
+
+    /// The type of the next value given by a wrapper(e.g, "timestamptz").
+    hint: Option<&'static str>,
+}
+
+/// A struct being serialized.
+enum Frame {
+    /// The outermost struct, map or tuple whose fields are columns(the number
+    /// of tuple elements written so far).
+    Row(usize),
+
+    /// A nested struct written as a composite value.
+    Record(Record),
+
+    /// A sequence written as an 1D array.
+    Array(Array),
+
+    /// A map written as an hstore.
+    Hstore(Hstore),
+
+    /// A map or a variant carrying data written as a jsonb.
+    #[cfg(feature = "serde_json")]
+    Json(Json),
+}
+
+struct Record {
+    /// The serde name of the struct(the name of the composite type if registered).
+    name: &'static str,
+
+    /// The number of fields written so far.
+    cnt: usize,
+
+    /// The inet/cidr declared for the compact form of an Ipv4Addr/Ipv6Addr(see
+    /// [`Ser::end_ip_octets`]).
+    ip: Option<&'static str>,
+
+    /// The fields written so far(oid, size, value).
+    buf: Vec<u8>,
+}
+
+struct Array {
+    /// The OID of the elements(declared or inferred from the first element).
+    elem: Option<u32>,
+
+    /// Whether the element type is given by the array type.
+    declared: bool,
+
+    /// The number of elements written so far.
+    cnt: usize,
+
+    has_null: bool,
+
+    /// The elements written so far(size, value).
+    buf: Vec<u8>,
+}
+
+struct Hstore {
+    /// The number of keys and values written so far.
+    cnt: usize,
+
+    /// The keys and values written so far(size, text).
+    buf: Vec<u8>,
+}
+
+#[cfg(feature = "serde_json")]
+struct Json {
+    /// The name of the variant tagging the value.
+    tag: Option<&'static str>,
+
+    /// The entries(an object) or the elements(an array) written so far.
+    val: Value,
+
+    /// The key of the next value.
+    key: Option<String>,
+}
+
+/// The type of a value.
+#[derive(Debug, Clone, Copy)]
+enum Typ {
+    /// A type name(e.g, "int4", "line_item").
+    Name(&'static str),
+
+    /// The array type of an element type(OID).
+    ArrayOf(u32),
 }
 
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum Error {
     Message(String),
 
     WriteError(String),
+
+    /// A field is larger than the maximum(see [`Config::with_max_field_len`]).
+    FieldTooLarge {
+        field: String,
+        size: usize,
+        max: usize,
+    },
+
+    /// A row is larger than the maximum(see [`Config::with_max_row_len`]).
+    RowTooLarge {
+        field: String,
+        size: usize,
+        max: usize,
+    },
+
+    /// A text value contains NUL characters(see [`Config::with_nul_policy`]).
+    NulInText {
+        field: String,
+    },
+
+    /// A text value contains a character unmappable to the target encoding.
+    UnmappableChar {
+        field: String,
+        ch: char,
+    },
+
+    /// The OID of a field of a composite value is unknown(see [`Config::with_registry`]).
+    UnknownType {
+        field: String,
+        typ: String,
+    },
+
+    /// A map is rejected by the [`MapPolicy`](see [`Config::with_map_policy`]).
+    UnsupportedMap {
+        field: String,
+    },
+
+    /// A variant is rejected by the [`EnumPolicy`](see [`Config::with_enum_policy`]).
+    UnsupportedVariant {
+        field: String,
+        variant: String,
+    },
 }
 
 impl Display for Error {
@@ -169,6 +393,26 @@ impl Display for Error {
         match self {
             Self::Message(msg) => write!(f, "{msg}"),
             Self::WriteError(msg) => write!(f, "Write Error: {msg}"),
+            Self::FieldTooLarge { field, size, max } => {
+                write!(f, "field {field} too large: {size} bytes(max: {max})")
+            }
+            Self::RowTooLarge { field, size, max } => {
+                write!(
+                    f,
+                    "row too large at field {field}: {size} bytes(max: {max})"
+                )
+            }
+            Self::NulInText { field } => write!(f, "field {field} contains a NUL character"),
+            Self::UnmappableChar { field, ch } => {
+                write!(f, "field {field} contains an unmappable character: {ch:?}")
+            }
+            Self::UnknownType { field, typ } => {
+                write!(f, "unknown type of field {field}: {typ}")
+            }
+            Self::UnsupportedMap { field } => write!(f, "unsupported map: {field}"),
+            Self::UnsupportedVariant { field, variant } => {
+                write!(f, "unsupported variant of field {field}: {variant}")
+            }
         }
     }
 }
@@ -184,7 +428,7 @@ impl ser::Error for Error {
 
 impl std::error::Error for Error {}
 
-impl<'a, W> SerializeSeq for &'a mut Ser<W>
+impl<W> SerializeSeq for &mut Ser<'_, W>
 where
     W: io::Write,
 {
@@ -199,173 +443,751 @@ where
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        Ok(())
+        match self.frames.pop() {
+            Some(Frame::Array(arr)) => self.end_array(arr),
+            _ => Ok(()),
+        }
     }
 }
 
-impl<'a, W> SerializeTuple for &'a mut Ser<W>
+impl<W> SerializeTuple for &mut Ser<'_, W>
 where
     W: io::Write,
 {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_element<T>(&mut self, _val: &T) -> Result<(), Self::Error>
+    fn serialize_element<T>(&mut self, val: &T) -> Result<(), Self::Error>
     where
         T: Serialize + ?Sized,
     {
-        todo!()
+        self.serialize_tuple_item(val)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        todo!()
+        self.end_struct()
     }
 }
 
-impl<'a, W> SerializeTupleStruct for &'a mut Ser<W>
+impl<W> SerializeTupleStruct for &mut Ser<'_, W>
 where
     W: io::Write,
 {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T>(&mut self, _val: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, val: &T) -> Result<(), Self::Error>
     where
         T: Serialize + ?Sized,
     {
-        todo!()
+        self.serialize_tuple_item(val)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        todo!()
+        self.end_struct()
     }
 }
 
-impl<'a, W> SerializeTupleVariant for &'a mut Ser<W>
+impl<W> SerializeTupleVariant for &mut Ser<'_, W>
 where
     W: io::Write,
 {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T>(&mut self, _val: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, val: &T) -> Result<(), Self::Error>
     where
         T: Serialize + ?Sized,
     {
-        todo!()
+        self.json_item(None, val)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        todo!()
+        self.end_variant()
     }
 }
 
-impl<'a, W> SerializeMap for &'a mut Ser<W>
+impl<W> SerializeMap for &mut Ser<'_, W>
 where
     W: io::Write,
 {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_key<T>(&mut self, _key: &T) -> Result<(), Self::Error>
+    /// The keys of a row are the names of the columns.
+    fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
     where
         T: Serialize + ?Sized,
     {
-        todo!()
+        match self.frames.last_mut() {
+            Some(Frame::Row(_)) => {
+                let name: String = key_name(capture(key)?)?;
+                self.path.push(Cow::Owned(name));
+                Ok(())
+            }
+            #[cfg(feature = "serde_json")]
+            Some(Frame::Json(js)) => {
+                js.key = Some(json_key(key)?);
+                Ok(())
+            }
+            _ => key.serialize(&mut **self),
+        }
     }
 
-    fn serialize_value<T>(&mut self, _val: &T) -> Result<(), Self::Error>
+    fn serialize_value<T>(&mut self, val: &T) -> Result<(), Self::Error>
     where
         T: Serialize + ?Sized,
     {
-        todo!()
+        match self.frames.last_mut() {
+            Some(Frame::Row(_)) => {
+                let r = val.serialize(&mut **self);
+                self.path.pop();
+                r
+            }
+            #[cfg(feature = "serde_json")]
+            Some(Frame::Json(js)) => {
+                let key: String = js
+                    .key
+                    .take()
+                    .ok_or_else(|| Error::Message("missing key of a json value".into()))?;
+                self.json_item(Some(key), val)
+            }
+            _ => val.serialize(&mut **self),
+        }
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        todo!()
+        match self.frames.pop() {
+            Some(Frame::Hstore(hs)) => self.end_hstore(hs),
+            #[cfg(feature = "serde_json")]
+            Some(Frame::Json(js)) => self.end_json(js),
+            _ => Ok(()),
+        }
     }
 }
 
-impl<'a, W> SerializeStruct for &'a mut Ser<W>
+impl<W> SerializeStruct for &mut Ser<'_, W>
 where
     W: io::Write,
 {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T>(&mut self, _key: &'static str, val: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, key: &'static str, val: &T) -> Result<(), Self::Error>
     where
         T: Serialize + ?Sized,
     {
-        val.serialize(&mut **self)
+        self.path.push(Cow::Borrowed(key));
+        let r = val.serialize(&mut **self);
+        self.path.pop();
+        r
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        Ok(())
+        self.end_struct()
     }
 }
 
-impl<'a, W> SerializeStructVariant for &'a mut Ser<W>
+impl<W> SerializeStructVariant for &mut Ser<'_, W>
 where
     W: io::Write,
 {
     type Ok = ();
     type Error = Error;
 
-    fn serialize_field<T>(&mut self, _key: &'static str, _val: &T) -> Result<(), Self::Error>
+    fn serialize_field<T>(&mut self, key: &'static str, val: &T) -> Result<(), Self::Error>
     where
         T: Serialize + ?Sized,
     {
-        todo!()
+        self.json_item(Some(key.into()), val)
     }
 
     fn end(self) -> Result<Self::Ok, Self::Error> {
-        todo!()
+        self.end_variant()
     }
 }
 
-impl<W> Ser<W>
+impl<W> Ser<'_, W>
 where
     W: io::Write,
 {
+    /// The dotted path of the field being serialized.
+    fn field(&self) -> String {
+        match self.path.is_empty() {
+            true => String::from("(unnamed)"),
+            false => self.path.join("."),
+        }
+    }
+
+    /// Writes the compact form of an [`IpAddr`] as an inet.
+    fn serialize_ip_addr<T>(&mut self, val: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let c: Captured = capture(val)?;
+        let ip: IpAddr = ip_addr(&c)
+            .ok_or_else(|| Error::Message(format!("unexpected form of an IpAddr: {c:?}")))?;
+        PgInet::from(ip).serialize(self)
+    }
+
+    /// Writes the compact form of a [`SocketAddr`] as a text(PostgreSQL has no socket type).
+    fn serialize_socket_addr<T>(&mut self, val: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let c: Captured = capture(val)?;
+        let sa: SocketAddr = socket_addr(&c)
+            .ok_or_else(|| Error::Message(format!("unexpected form of a SocketAddr: {c:?}")))?;
+        self.serialize_str(&sa.to_string())
+    }
+
+    /// Applies the [`NulPolicy`] to a text value.
+    fn text<'s>(&self, s: &'s str) -> Result<Cow<'s, str>, Error> {
+        if !s.contains('\0') {
+            return Ok(Cow::Borrowed(s));
+        }
+        match self.cfg.nul_policy {
+            NulPolicy::Error => Err(Error::NulInText {
+                field: self.field(),
+            }),
+            NulPolicy::Strip => Ok(Cow::Owned(s.replace('\0', ""))),
+            NulPolicy::Replace(c) => {
+                let mut buf: [u8; 4] = [0; 4];
+                let r: &str = c.encode_utf8(&mut buf);
+                Ok(Cow::Owned(s.replace('\0', r)))
+            }
+        }
+    }
+
+    /// Whether values are written into a composite value, an array or an hstore.
+    fn in_container(&self) -> bool {
+        matches!(
+            self.frames.last(),
+            Some(Frame::Record(_)) | Some(Frame::Array(_)) | Some(Frame::Hstore(_))
+        )
+    }
+
+    /// Rejects a container(e.g, a struct) inside an hstore.
+    fn check_hstore_item(&self, typ: Typ) -> Result<(), Error> {
+        match (self.frames.last(), typ) {
+            (Some(Frame::Hstore(_)), Typ::Name("text")) => Ok(()),
+            (Some(Frame::Hstore(_)), _) => Err(Error::Message(format!(
+                "keys and values of the hstore {} must be strings",
+                self.field()
+            ))),
+            _ => Ok(()),
+        }
+    }
+
+    /// Writes a non-null value as a column, a field of a composite value or an
+    /// element of an array.
+    ///
+    /// The type is used for the OID unless hinted by a wrapper.
+    fn write_value(&mut self, typ: Typ, b: &[u8]) -> Result<(), Error> {
+        let typ: Typ = match self.hint.take() {
+            Some("anyarray") => Typ::ArrayOf(array_elem_oid(b)?),
+            Some(h) => Typ::Name(h),
+            None => typ,
+        };
+        self.check_hstore_item(typ)?;
+        if !self.in_container() {
+            self.serialize_len(b.len())?;
+            return self
+                .wtr
+                .write_all(b)
+                .map_err(|e| Error::WriteError(format!("unable to write a value: {e}")));
+        }
+        let sz: i32 = self.check_field_len(b.len())?;
+        if let Some(Frame::Hstore(hs)) = self.frames.last_mut() {
+            hs.buf.extend_from_slice(&sz.to_be_bytes());
+            hs.buf.extend_from_slice(b);
+            hs.cnt += 1;
+            return Ok(());
+        }
+        let oid: u32 = self.item_oid(Some(typ))?;
+        match self.frames.last_mut() {
+            Some(Frame::Record(rec)) => {
+                rec.buf.extend_from_slice(&oid.to_be_bytes());
+                rec.buf.extend_from_slice(&sz.to_be_bytes());
+                rec.buf.extend_from_slice(b);
+                rec.cnt += 1;
+            }
+            Some(Frame::Array(arr)) => {
+                arr.elem = Some(oid);
+                arr.buf.extend_from_slice(&sz.to_be_bytes());
+                arr.buf.extend_from_slice(b);
+                arr.cnt += 1;
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Writes a null as a column, a field of a composite value or an element
+    /// of an array.
+    fn write_null(&mut self) -> Result<(), Error> {
+        self.hint = None;
+        if !self.in_container() {
+            return self.serialize_col_size(-1);
+        }
+        let null: [u8; 4] = (-1i32).to_be_bytes();
+        if let Some(Frame::Hstore(hs)) = self.frames.last() {
+            if 0 == hs.cnt % 2 {
+                return Err(Error::Message(format!(
+                    "keys of the hstore {} must not be null",
+                    self.field()
+                )));
+            }
+        }
+        if let Some(Frame::Hstore(hs)) = self.frames.last_mut() {
+            hs.buf.extend_from_slice(&null);
+            hs.cnt += 1;
+            return Ok(());
+        }
+        if let Some(Frame::Array(arr)) = self.frames.last_mut() {
+            arr.has_null = true;
+            arr.buf.extend_from_slice(&null);
+            arr.cnt += 1;
+            return Ok(());
+        }
+        let oid: u32 = self.item_oid(None)?;
+        if let Some(Frame::Record(rec)) = self.frames.last_mut() {
+            rec.buf.extend_from_slice(&oid.to_be_bytes());
+            rec.buf.extend_from_slice(&null);
+            rec.cnt += 1;
+        }
+        Ok(())
+    }
+
+    /// The OID of a type.
+    fn resolve(&self, typ: Typ) -> Result<u32, Error> {
+        let reg = &self.cfg.registry;
+        match typ {
+            Typ::Name(name) => reg.oid(name).ok_or_else(|| Error::UnknownType {
+                field: self.field(),
+                typ: name.into(),
+            }),
+            Typ::ArrayOf(elem) => reg.array_oid(elem).ok_or_else(|| Error::UnknownType {
+                field: self.field(),
+                typ: format!("array of {elem}"),
+            }),
+        }
+    }
+
+    /// The declared OID of the next field of a registered composite type or
+    /// the next element of a typed array.
+    fn declared_item_oid(&self) -> Option<u32> {
+        match self.frames.last() {
+            Some(Frame::Array(arr)) if arr.declared => arr.elem,
+            _ => self.declared_field_oid(),
+        }
+    }
+
+    /// The declared OID of the next field of a registered composite type.
+    fn declared_field_oid(&self) -> Option<u32> {
+        let Some(Frame::Record(rec)) = self.frames.last() else {
+            return None;
+        };
+        self.cfg
+            .registry
+            .composite(rec.name)
+            .and_then(|fields| fields.get(rec.cnt))
+            .copied()
+    }
+
+    /// The OID of the next field of a composite value or the next element of
+    /// an array.
+    ///
+    /// The field types of a registered composite type and the element type of
+    /// a typed array take precedence.
+    fn item_oid(&self, typ: Option<Typ>) -> Result<u32, Error> {
+        match self.frames.last() {
+            Some(Frame::Record(rec)) => {
+                if let Some(fields) = self.cfg.registry.composite(rec.name) {
+                    let declared: u32 = fields.get(rec.cnt).copied().ok_or_else(|| {
+                        Error::Message(format!(
+                            "too many fields for the composite type {}: {}",
+                            rec.name,
+                            rec.cnt + 1
+                        ))
+                    })?;
+                    return match typ {
+                        Some(typ) => self.check_field_type(rec.name, declared, typ),
+                        None => Ok(declared),
+                    };
+                }
+                let typ: Typ = typ.ok_or_else(|| {
+                    Error::Message(format!(
+                        "unable to infer the type of the null field {}(register the composite type {})",
+                        self.field(),
+                        rec.name
+                    ))
+                })?;
+                self.resolve(typ)
+            }
+            Some(Frame::Array(arr)) => {
+                if let (true, Some(elem)) = (arr.declared, arr.elem) {
+                    return Ok(elem);
+                }
+                let typ: Typ = typ.ok_or_else(|| Error::Message("null element".into()))?;
+                let oid: u32 = self.resolve(typ)?;
+                match arr.elem {
+                    Some(elem) if elem != oid => Err(Error::Message(format!(
+                        "elements of the array {} have different types: {elem}, {oid}",
+                        self.field()
+                    ))),
+                    _ => Ok(oid),
+                }
+            }
+            _ => Err(Error::Message(
+                "not in a composite value or an array".into(),
+            )),
+        }
+    }
+
+    /// Checks a field value of a registered composite type against the
+    /// declared type.
+    ///
+    /// A built-in declared type must be the type of the value, except that the
+    /// text types(text, varchar, bpchar, json) accept each other. Other declared
+    /// types(e.g, an enum, a domain or an extension type) accept any value
+    /// since their binary formats are unknown.
+    fn check_field_type(&self, name: &str, declared: u32, typ: Typ) -> Result<u32, Error> {
+        let oid: u32 = self.resolve(typ)?;
+        match oid == declared || !is_builtin(declared) || (is_text(oid) && is_text(declared)) {
+            true => Ok(declared),
+            false => Err(Error::Message(format!(
+                "field {} of the composite type {name} is of the type {declared}: {oid}",
+                self.field()
+            ))),
+        }
+    }
+
+    /// Pushes a row(the outermost) or a composite value for a struct or a tuple.
+    fn begin_struct(&mut self, name: &'static str, len: usize) -> Result<(), Error> {
+        self.hint = None;
+        self.check_hstore_item(Typ::Name(name))?;
+        let frame: Frame = match self.frames.is_empty() {
+            true => Frame::Row(0),
+            false => Frame::Record(Record {
+                name,
+                cnt: 0,
+                ip: None,
+                buf: Vec::with_capacity(len * 12),
+            }),
+        };
+        self.frames.push(frame);
+        Ok(())
+    }
+
+    /// The inet/cidr declared by the type hint or the registry for a nested
+    /// tuple of 4 or 16 elements(the compact form of an Ipv4Addr/Ipv6Addr).
+    fn ip_octets_type(&self, len: usize) -> Option<&'static str> {
+        if !matches!(len, 4 | 16) || self.frames.is_empty() {
+            return None;
+        }
+        let reg = &self.cfg.registry;
+        let target: u32 = match self.hint {
+            Some(h) => reg.oid(h),
+            None => self.declared_item_oid(),
+        }?;
+        ["inet", "cidr"]
+            .into_iter()
+            .find(|t| reg.oid(t) == Some(target))
+    }
+
+    /// The element type of a nested tuple written as an array(e.g, a [i32; 3]
+    /// for an int4[] field of a registered composite type).
+    ///
+    /// The elements must be of the element type.
+    fn tuple_array_elem(&mut self) -> Result<Option<u32>, Error> {
+        if self.frames.is_empty() {
+            return Ok(None);
+        }
+        let reg = &self.cfg.registry;
+        let target: Option<u32> = match self.hint {
+            Some(h) => reg.oid(h),
+            None => self.declared_field_oid(),
+        };
+        let Some(elem) = target.and_then(|a| reg.elem_oid(a)) else {
+            return Ok(None);
+        };
+        self.hint = None;
+        self.check_hstore_item(Typ::Name("array"))?;
+        if let Some(Frame::Array(_)) = self.frames.last() {
+            return Err(Error::Message(format!(
+                "multidimensional arrays are unsupported: {}",
+                self.field()
+            )));
+        }
+        Ok(Some(elem))
+    }
+
+    /// Writes an element of a tuple named by its index(e.g, "pair.0").
+    fn serialize_tuple_item<T>(&mut self, val: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        let ix: usize = match self.frames.last_mut() {
+            Some(Frame::Row(cnt)) => {
+                *cnt += 1;
+                *cnt - 1
+            }
+            Some(Frame::Record(rec)) => rec.cnt,
+            Some(Frame::Array(arr)) => arr.cnt,
+            _ => 0,
+        };
+        self.path.push(Cow::Owned(ix.to_string()));
+        let r = val.serialize(&mut *self);
+        self.path.pop();
+        r
+    }
+
+    /// Writes a struct or a tuple.
+    fn end_struct(&mut self) -> Result<(), Error> {
+        match self.frames.pop() {
+            Some(Frame::Record(rec)) if rec.ip.is_some() => self.end_ip_octets(rec),
+            Some(Frame::Record(rec)) => self.end_record(rec),
+            Some(Frame::Array(arr)) => self.end_array(arr),
+            _ => Ok(()),
+        }
+    }
+
+    /// Writes the compact form of an Ipv4Addr/Ipv6Addr declared as an inet or
+    /// a cidr.
+    ///
+    /// Undeclared ones are composite values since they cannot be told from a
+    /// [u8; 4] or a [u8; 16].
+    fn end_ip_octets(&mut self, rec: Record) -> Result<(), Error> {
+        // each field is an int2 of an u8(oid 21, size 2, 2 bytes)
+        let octets: Option<Vec<u8>> = rec
+            .buf
+            .chunks(10)
+            .map(|f| match f {
+                [0, 0, 0, 21, 0, 0, 0, 2, 0, b] => Some(*b),
+                _ => None,
+            })
+            .collect();
+        let ip: IpAddr = octets
+            .and_then(|o| ip_addr(&Captured::Bytes(o)))
+            .ok_or_else(|| Error::Message(format!("invalid octets of {}", self.field())))?;
+        match rec.ip {
+            Some("cidr") => PgCidr::from(ip).serialize(self),
+            _ => PgInet::from(ip).serialize(self),
+        }
+    }
+
+    /// Writes a composite value(the number of fields, then the fields).
+    fn end_record(&mut self, rec: Record) -> Result<(), Error> {
+        let reg = &self.cfg.registry;
+        if let Some(fields) = reg.composite(rec.name) {
+            if fields.len() != rec.cnt {
+                return Err(Error::Message(format!(
+                    "composite type {} has {} fields: {}",
+                    rec.name,
+                    fields.len(),
+                    rec.cnt
+                )));
+            }
+        }
+        // only a column may be an anonymous record: a composite value and an
+        // array need the type of their fields and elements
+        let oid: Option<u32> = reg.oid(rec.name).filter(|o| *o != RECORD_OID);
+        let typ: &'static str = match (oid, self.frames.last()) {
+            (Some(_), _) => rec.name,
+            (None, Some(Frame::Row(_))) => "record",
+            (None, _) => {
+                return Err(Error::UnknownType {
+                    field: self.field(),
+                    typ: rec.name.into(),
+                })
+            }
+        };
+        let cnt: i32 = i32::try_from(rec.cnt)
+            .map_err(|_| Error::Message(format!("too many fields: {}", rec.cnt)))?;
+        let mut buf: Vec<u8> = Vec::with_capacity(4 + rec.buf.len());
+        buf.extend_from_slice(&cnt.to_be_bytes());
+        buf.extend_from_slice(&rec.buf);
+        self.write_value(Typ::Name(typ), &buf)
+    }
+
+    /// Writes an hstore(the number of pairs, then the keys and values).
+    fn end_hstore(&mut self, hs: Hstore) -> Result<(), Error> {
+        let cnt: i32 = i32::try_from(hs.cnt / 2)
+            .map_err(|_| Error::Message(format!("too many pairs: {}", hs.cnt / 2)))?;
+        let mut buf: Vec<u8> = Vec::with_capacity(4 + hs.buf.len());
+        buf.extend_from_slice(&cnt.to_be_bytes());
+        buf.extend_from_slice(&hs.buf);
+        self.write_value(Typ::Name("hstore"), &buf)
+    }
+
+    /// Writes a jsonb(the version, then the text).
+    #[cfg(feature = "serde_json")]
+    fn write_json(&mut self, v: &Value) -> Result<(), Error> {
+        let mut buf: Vec<u8> = vec![1];
+        serde_json::to_writer(&mut buf, v)
+            .map_err(|e| Error::Message(format!("unable to write a json: {e}")))?;
+        self.write_value(Typ::Name("jsonb"), &buf)
+    }
+
+    /// Writes a map or a variant carrying data as a jsonb.
+    #[cfg(feature = "serde_json")]
+    fn end_json(&mut self, js: Json) -> Result<(), Error> {
+        match js.tag {
+            Some(tag) => self.write_json(&tagged(tag, js.val)),
+            None => self.write_json(&js.val),
+        }
+    }
+
+    /// Adds an entry(key given) or an element to the jsonb being written.
+    #[cfg_attr(not(feature = "serde_json"), allow(unused_variables))]
+    fn json_item<T>(&mut self, key: Option<String>, val: &T) -> Result<(), Error>
+    where
+        T: Serialize + ?Sized,
+    {
+        #[cfg(feature = "serde_json")]
+        if let Some(Frame::Json(js)) = self.frames.last_mut() {
+            let v: Value = to_json(val)?;
+            match (&mut js.val, key) {
+                (Value::Object(m), Some(k)) => {
+                    m.insert(k, v);
+                }
+                (Value::Array(a), None) => a.push(v),
+                _ => return Err(Error::Message("unexpected item of a json".into())),
+            }
+            return Ok(());
+        }
+        Err(Error::Message(format!("not in a jsonb: {}", self.field())))
+    }
+
+    /// Writes a tuple or struct variant.
+    fn end_variant(&mut self) -> Result<(), Error> {
+        match self.frames.pop() {
+            #[cfg(feature = "serde_json")]
+            Some(Frame::Json(js)) => self.end_json(js),
+            _ => Err(Error::Message(format!("not in a jsonb: {}", self.field()))),
+        }
+    }
+
+    /// Pushes a jsonb for a variant carrying data if allowed by the [`EnumPolicy`].
+    #[cfg_attr(not(feature = "serde_json"), allow(unused_variables))]
+    fn begin_variant(
+        &mut self,
+        name: &'static str,
+        var: &'static str,
+        fields: Option<usize>,
+    ) -> Result<(), Error> {
+        self.hint = None;
+        match self.cfg.enum_policy {
+            #[cfg(feature = "serde_json")]
+            EnumPolicy::Jsonb => {
+                let val: Value = match fields {
+                    Some(len) => Value::Array(Vec::with_capacity(len)),
+                    None => Value::Object(Map::new()),
+                };
+                self.frames.push(Frame::Json(Json {
+                    tag: Some(var),
+                    val,
+                    key: None,
+                }));
+                Ok(())
+            }
+            _ => Err(self.unsupported_variant(name, var)),
+        }
+    }
+
+    fn unsupported_variant(&self, name: &'static str, var: &'static str) -> Error {
+        Error::UnsupportedVariant {
+            field: self.field(),
+            variant: format!("{name}::{var}"),
+        }
+    }
+
+    /// The type of an enum label inside a composite value or an array: the
+    /// enum type if registered, else a text.
+    fn label_type(&self, name: &'static str) -> Option<&'static str> {
+        match (self.cfg.registry.oid(name), self.frames.last()) {
+            (Some(_), Some(Frame::Record(_))) | (Some(_), Some(Frame::Array(_))) => Some(name),
+            _ => None,
+        }
+    }
+
+    /// Writes an 1D array(a header, then the elements).
+    fn end_array(&mut self, arr: Array) -> Result<(), Error> {
+        let elem: u32 = arr.elem.ok_or_else(|| {
+            Error::Message(format!(
+                "unable to infer the element type of the array {}",
+                self.field()
+            ))
+        })?;
+        let cnt: i32 = i32::try_from(arr.cnt)
+            .map_err(|_| Error::Message(format!("too many array items: {}", arr.cnt)))?;
+        let mut buf: Vec<u8> = Vec::with_capacity(20 + arr.buf.len());
+        let ndim: i32 = match cnt {
+            0 => 0,
+            _ => 1,
+        };
+        buf.extend_from_slice(&ndim.to_be_bytes());
+        buf.extend_from_slice(&i32::from(arr.has_null).to_be_bytes());
+        buf.extend_from_slice(&elem.to_be_bytes());
+        if 0 < cnt {
+            buf.extend_from_slice(&cnt.to_be_bytes());
+            buf.extend_from_slice(&1i32.to_be_bytes());
+        }
+        buf.extend_from_slice(&arr.buf);
+        self.write_value(Typ::ArrayOf(elem), &buf)
+    }
+
     fn serialize_col_size(&mut self, sz: i32) -> Result<(), Error> {
         let ib: [u8; 4] = sz.to_be_bytes();
         self.wtr
             .write_all(&ib)
             .map_err(|e| Error::WriteError(format!("unable to write a size of a column: {e}")))?;
+        self.row_len += ib.len();
         Ok(())
     }
-}
 
-macro_rules! serialize_signed {
-    ($ity: ty, $name: ident) => {
-        fn $name(self, i: $ity) -> Result<Self::Ok, Self::Error> {
-            const SZ: i32 = core::mem::size_of::<$ity>() as i32;
-            self.serialize_col_size(SZ)?;
-            let b: [u8; SZ as usize] = i.to_be_bytes();
-            self.wtr
-                .write_all(&b)
-                .map_err(|e| Error::WriteError(format!("unable to write an integer: {e}")))?;
-            Ok(())
+    /// Checks the maximum size of a field.
+    fn check_field_len(&self, len: usize) -> Result<i32, Error> {
+        let max: usize = self.cfg.max_field_len.min(i32::MAX as usize);
+        i32::try_from(len)
+            .ok()
+            .filter(|_| len <= max)
+            .ok_or_else(|| Error::FieldTooLarge {
+                field: self.field(),
+                size: len,
+                max,
+            })
+    }
+
+    /// Checks the limits and writes the size of a non-null column.
+    fn serialize_len(&mut self, len: usize) -> Result<(), Error> {
+        let sz: i32 = self.check_field_len(len)?;
+        let row_len: usize = self.row_len.saturating_add(4).saturating_add(len);
+        if let Some(max) = self.cfg.max_row_len.filter(|m| row_len > *m) {
+            return Err(Error::RowTooLarge {
+                field: self.field(),
+                size: row_len,
+                max,
+            });
         }
-    };
+        self.serialize_col_size(sz)?;
+        self.row_len += len;
+        Ok(())
+    }
 }
 
-macro_rules! serialize_float {
-    ($fty: ty, $name: ident) => {
-        fn $name(self, f: $fty) -> Result<Self::Ok, Self::Error> {
-            const SZ: i32 = core::mem::size_of::<$fty>() as i32;
-            self.serialize_col_size(SZ)?;
-            let b: [u8; SZ as usize] = f.to_be_bytes();
-            self.wtr
-                .write_all(&b)
-                .map_err(|e| Error::WriteError(format!("unable to write a float value: {e}")))?;
-            Ok(())
+macro_rules! serialize_num {
+    ($nty: ty, $name: ident, $typ: literal) => {
+        fn $name(self, n: $nty) -> Result<Self::Ok, Self::Error> {
+            const SZ: usize = core::mem::size_of::<$nty>();
+            let b: [u8; SZ] = n.to_be_bytes();
+            self.write_value(Typ::Name($typ), &b)
         }
     };
 }
 
-impl<'a, W> Serializer for &'a mut Ser<W>
+impl<W> Serializer for &mut Ser<'_, W>
 where
     W: io::Write,
 {
@@ -387,24 +1209,19 @@ where
             true => 1,
             false => 0,
         };
-        let sz: i32 = 1;
-        self.serialize_col_size(sz)?;
-        self.wtr
-            .write_all(&[u])
-            .map_err(|e| Error::WriteError(format!("unable to write a bool value: {e}")))?;
-        Ok(())
+        self.write_value(Typ::Name("bool"), &[u])
     }
 
     fn serialize_i8(self, i: i8) -> Result<Self::Ok, Self::Error> {
         self.serialize_i16(i.into())
     }
 
-    serialize_signed!(i16, serialize_i16);
-    serialize_signed!(i32, serialize_i32);
-    serialize_signed!(i64, serialize_i64);
+    serialize_num!(i16, serialize_i16, "int2");
+    serialize_num!(i32, serialize_i32, "int4");
+    serialize_num!(i64, serialize_i64, "int8");
 
-    serialize_float!(f32, serialize_f32);
-    serialize_float!(f64, serialize_f64);
+    serialize_num!(f32, serialize_f32, "float4");
+    serialize_num!(f64, serialize_f64, "float8");
 
     /// postgresql does not support i128
     fn serialize_i128(self, _i: i128) -> Result<Self::Ok, Self::Error> {
@@ -439,25 +1256,41 @@ where
     fn serialize_char(self, c: char) -> Result<Self::Ok, Self::Error> {
         let mut buf: [u8; 4] = [0; 4];
         let encoded: &mut str = c.encode_utf8(&mut buf);
-        let b: &[u8] = encoded.as_bytes();
-        self.serialize_bytes(b)
+        self.serialize_str(encoded)
     }
     fn serialize_str(self, s: &str) -> Result<Self::Ok, Self::Error> {
-        self.serialize_bytes(s.as_bytes())
+        let t: Cow<str> = self.text(s)?;
+
+        #[cfg(feature = "encoding_rs")]
+        if let Some(enc) = self.cfg.encoding {
+            let b: Cow<[u8]> = enc.encode(&t).map_err(|ch| Error::UnmappableChar {
+                field: self.field(),
+                ch,
+            })?;
+            return self.write_value(Typ::Name("text"), &b);
+        }
+
+        self.write_value(Typ::Name("text"), t.as_bytes())
     }
 
+    /// Bytes are written as a bytea.
+    ///
+    /// Undeclared 16 bytes inside an array or an unregistered composite value
+    /// are rejected: they are the compact form of a `uuid::Uuid` as well. Use
+    /// a [`PgNumArray`] for a uuid[], or a [`crate::blob::PgBlob`] for a bytea.
     fn serialize_bytes(self, b: &[u8]) -> Result<Self::Ok, Self::Error> {
-        let sz: i32 = b.len() as i32;
-        self.serialize_col_size(sz)?;
-        self.wtr
-            .write_all(b)
-            .map_err(|e| Error::WriteError(format!("unable to write bytes: {e}")))?;
-        Ok(())
+        let item: bool = matches!(self.frames.last(), Some(Frame::Record(_) | Frame::Array(_)));
+        if item && 16 == b.len() && self.hint.is_none() && self.declared_item_oid().is_none() {
+            return Err(Error::Message(format!(
+                "ambiguous 16 bytes of {}: use PgNumArray<uuid::Uuid> for a uuid[], or PgBlob for a bytea",
+                self.field()
+            )));
+        }
+        self.write_value(Typ::Name("bytea"), b)
     }
 
     fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
-        let sz: i32 = -1;
-        self.serialize_col_size(sz)
+        self.write_null()
     }
     fn serialize_some<T>(self, t: &T) -> Result<Self::Ok, Self::Error>
     where
@@ -472,90 +1305,308 @@ where
     fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
         self.serialize_unit()
     }
+    /// Unit variants are written as configured by [`Config::with_enum_policy`].
     fn serialize_unit_variant(
         self,
-        _name: &'static str,
-        _vix: u32,
+        name: &'static str,
+        vix: u32,
         var: &'static str,
     ) -> Result<Self::Ok, Self::Error> {
-        self.serialize_str(var)
-    }
-
-    fn serialize_newtype_struct<T>(
-        self,
-        _name: &'static str,
+        match self.cfg.enum_policy {
+            EnumPolicy::Label(case) => {
+                let label: Cow<str> = case.apply(var);
+                self.hint = self.label_type(name);
+                self.serialize_str(&label)
+            }
+            EnumPolicy::Int2 => {
+                let i: i16 = i16::try_from(vix).map_err(|_| {
+                    Error::Message(format!(
+                        "too large index of the variant {name}::{var}: {vix}"
+                    ))
+                })?;
+                self.serialize_i16(i)
+            }
+            EnumPolicy::Int4 => {
+                let i: i32 = i32::try_from(vix).map_err(|_| {
+                    Error::Message(format!(
+                        "too large index of the variant {name}::{var}: {vix}"
+                    ))
+                })?;
+                self.serialize_i32(i)
+            }
+            #[cfg(feature = "serde_json")]
+            EnumPolicy::Jsonb => {
+                self.hint = None;
+                self.write_json(&Value::String(var.into()))
+            }
+        }
+    }
+
+    /// Wrappers(e.g, [`crate::time::systemtime::Timestampz`]) name their types here.
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
         val: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
         T: Serialize + ?Sized,
     {
+        if let Some(typ) = name.strip_prefix(TYPE_HINT_PREFIX) {
+            self.hint = Some(typ);
+        }
         val.serialize(self)
     }
 
+    /// IpAddr/SocketAddr in the compact form are written as an inet/text.
+    /// Variants carrying data are written as jsonbs(see [`Config::with_enum_policy`]).
     fn serialize_newtype_variant<T>(
         self,
-        _name: &'static str,
+        name: &'static str,
         _vix: u32,
-        _var: &'static str,
+        var: &'static str,
         val: &T,
     ) -> Result<Self::Ok, Self::Error>
     where
         T: Serialize + ?Sized,
     {
-        val.serialize(self)
+        match (self.cfg.human_readable, name, var) {
+            (false, "IpAddr", "V4" | "V6") => return self.serialize_ip_addr(val),
+            (false, "SocketAddr", "V4" | "V6") => return self.serialize_socket_addr(val),
+            _ => {}
+        }
+        match self.cfg.enum_policy {
+            #[cfg(feature = "serde_json")]
+            EnumPolicy::Jsonb => {
+                self.hint = None;
+                self.write_json(&tagged(var, to_json(val)?))
+            }
+            _ => Err(self.unsupported_variant(name, var)),
+        }
     }
 
-    /// the use of native Vec is unsupported.
-    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
-        todo!()
+    /// Sequences are written as 1D arrays(nested sequences are unsupported).
+    ///
+    /// The element type is given by a typed array(e.g, [`PgTextArray`]) or by
+    /// the field of a registered composite type, or inferred from the elements.
+    ///
+    /// A `Vec<u8>` is an int2[](use [`crate::blob::as_bytea`] for a bytea).
+    fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        let hint: Option<&'static str> = self.hint.take();
+        self.check_hstore_item(Typ::Name("array"))?;
+        if let Some(Frame::Array(_)) = self.frames.last() {
+            return Err(Error::Message(format!(
+                "multidimensional arrays are unsupported: {}",
+                self.field()
+            )));
+        }
+        let reg = &self.cfg.registry;
+        let elem: Option<u32> = match hint {
+            Some(h) => Some(reg.oid(h).and_then(|a| reg.elem_oid(a)).ok_or_else(|| {
+                Error::UnknownType {
+                    field: self.field(),
+                    typ: h.into(),
+                }
+            })?),
+            None => self.declared_field_oid().and_then(|a| reg.elem_oid(a)),
+        };
+        self.frames.push(Frame::Array(Array {
+            elem,
+            declared: elem.is_some(),
+            cnt: 0,
+            has_null: false,
+            buf: Vec::with_capacity(len.unwrap_or(0) * 8),
+        }));
+        Ok(self)
     }
 
-    /// the use of native Tuple is unsupported.
-    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
-        todo!()
+    /// The outermost tuple is a row(one column per element); nested tuples
+    /// are anonymous composite values(records).
+    ///
+    /// Fixed size arrays(e.g, [i32; 3]) are tuples for serde: they are written
+    /// as 1D arrays only if the field of a registered composite type is an
+    /// array type(e.g, int4[]), else as records. Use a `Vec` or a
+    /// [`PgNumArray`] for an array column.
+    ///
+    /// Likewise, the compact form of an Ipv4Addr/Ipv6Addr(4 or 16 u8s) is an
+    /// inet/cidr only if declared so, else a record. Wrap it in a [`PgInet`].
+    fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        let ip: Option<&'static str> = self.ip_octets_type(len);
+        match self.tuple_array_elem()? {
+            Some(elem) => self.frames.push(Frame::Array(Array {
+                elem: Some(elem),
+                declared: false,
+                cnt: 0,
+                has_null: false,
+                buf: Vec::with_capacity(len * 8),
+            })),
+            None => self.begin_struct("record", len)?,
+        }
+        if let (Some(_), Some(Frame::Record(rec))) = (ip, self.frames.last_mut()) {
+            rec.ip = ip;
+        }
+        Ok(self)
     }
 
-    /// the use of native Tuple is unsupported.
+    /// The outermost tuple struct is a row; nested tuple structs are composite
+    /// values named like structs.
     fn serialize_tuple_struct(
         self,
-        _name: &'static str,
-        _len: usize,
+        name: &'static str,
+        len: usize,
     ) -> Result<Self::SerializeTupleStruct, Self::Error> {
-        todo!()
+        self.begin_struct(name, len)?;
+        Ok(self)
     }
 
-    /// the use of native Tuple is unsupported.
+    /// Variants carrying data are written as jsonbs(see [`Config::with_enum_policy`]).
     fn serialize_tuple_variant(
         self,
-        _name: &'static str,
+        name: &'static str,
         _vix: u32,
-        _var: &'static str,
-        _len: usize,
+        var: &'static str,
+        len: usize,
     ) -> Result<Self::SerializeTupleVariant, Self::Error> {
-        todo!()
+        self.begin_variant(name, var, Some(len))?;
+        Ok(self)
     }
 
-    /// map is unsupported
-    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-        todo!()
+    /// The outermost map is a row(e.g, a struct using `#[serde(flatten)]`).
+    ///
+    /// Nested maps are written as hstores if wrapped by [`crate::hstore::PgHstore`],
+    /// or as configured by [`Config::with_map_policy`].
+    fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        let hint: Option<&'static str> = self.hint.take();
+        self.check_hstore_item(Typ::Name("hstore"))?;
+        let hstore = Frame::Hstore(Hstore {
+            cnt: 0,
+            buf: Vec::with_capacity(len.unwrap_or(0) * 16),
+        });
+        let frame: Frame = match (hint, self.frames.is_empty(), self.cfg.map_policy) {
+            (Some("hstore"), _, _) => hstore,
+            (_, true, _) => Frame::Row(0),
+            (_, false, MapPolicy::Hstore) => hstore,
+            #[cfg(feature = "serde_json")]
+            (_, false, MapPolicy::Jsonb) => Frame::Json(Json {
+                tag: None,
+                val: Value::Object(Map::new()),
+                key: None,
+            }),
+            (_, false, MapPolicy::Error) => {
+                return Err(Error::UnsupportedMap {
+                    field: self.field(),
+                })
+            }
+        };
+        self.frames.push(frame);
+        Ok(self)
     }
 
+    /// The outermost struct is a row; nested structs are composite values.
+    ///
+    /// A column may be an anonymous record, but a struct inside a composite
+    /// value or an array must be a registered composite type.
     fn serialize_struct(
         self,
-        _name: &'static str,
-        _len: usize,
+        name: &'static str,
+        len: usize,
     ) -> Result<Self::SerializeStruct, Self::Error> {
+        self.begin_struct(name, len)?;
         Ok(self)
     }
 
+    /// Variants carrying data are written as jsonbs(see [`Config::with_enum_policy`]).
     fn serialize_struct_variant(
         self,
-        _name: &'static str,
+        name: &'static str,
         _vix: u32,
-        _var: &'static str,
+        var: &'static str,
         _len: usize,
     ) -> Result<Self::SerializeStructVariant, Self::Error> {
-        unimplemented!()
+        self.begin_variant(name, var, None)?;
+        Ok(self)
+    }
+
+    fn is_human_readable(&self) -> bool {
+        self.cfg.human_readable
+    }
+}
+
+/// The element OID in the header of an encoded array(hinted as "anyarray").
+fn array_elem_oid(b: &[u8]) -> Result<u32, Error> {
+    b.get(8..12)
+        .and_then(|o| <[u8; 4]>::try_from(o).ok())
+        .map(u32::from_be_bytes)
+        .ok_or_else(|| Error::Message(format!("invalid array header: {} bytes", b.len())))
+}
+
+/// Converts a captured key of a row to the name of a column.
+fn key_name(c: Captured) -> Result<String, Error> {
+    match c {
+        Captured::Str(s) => Ok(s),
+        Captured::Int(i) => Ok(i.to_string()),
+        _ => Err(Error::Message(format!(
+            "unsupported name of a column: {c:?}"
+        ))),
+    }
+}
+
+/// Converts a key of a map to the key of a json object.
+#[cfg(feature = "serde_json")]
+fn json_key<T>(key: &T) -> Result<String, Error>
+where
+    T: Serialize + ?Sized,
+{
+    match to_json(key)? {
+        Value::String(s) => Ok(s),
+        Value::Number(n) => Ok(n.to_string()),
+        Value::Bool(b) => Ok(b.to_string()),
+        v => Err(Error::Message(format!("unsupported key of a json: {v}"))),
+    }
+}
+
+#[cfg(feature = "serde_json")]
+fn to_json<T>(val: &T) -> Result<Value, Error>
+where
+    T: Serialize + ?Sized,
+{
+    serde_json::to_value(val).map_err(|e| Error::Message(format!("unable to convert to json: {e}")))
+}
+
+/// The externally tagged form of a variant(e.g, {"Circle":{"r":1.0}}).
+#[cfg(feature = "serde_json")]
+fn tagged(tag: &'static str, val: Value) -> Value {
+    let mut m: Map<String, Value> = Map::new();
+    m.insert(tag.into(), val);
+    Value::Object(m)
+}
+
+/// Converts the captured octets of an Ipv4Addr/Ipv6Addr.
+fn ip_addr(c: &Captured) -> Option<IpAddr> {
+    let o: Vec<u8> = c.octets()?;
+    match o.len() {
+        4 => <[u8; 4]>::try_from(o)
+            .ok()
+            .map(Ipv4Addr::from)
+            .map(IpAddr::V4),
+        16 => <[u8; 16]>::try_from(o)
+            .ok()
+            .map(Ipv6Addr::from)
+            .map(IpAddr::V6),
+        _ => None,
+    }
+}
+
+/// Converts the captured (ip, port) of a SocketAddrV4/SocketAddrV6.
+fn socket_addr(c: &Captured) -> Option<SocketAddr> {
+    match c {
+        Captured::Seq(items) => match items.as_slice() {
+            [ip, Captured::Int(port)] => {
+                let port: u16 = u16::try_from(*port).ok()?;
+                Some(SocketAddr::new(ip_addr(ip)?, port))
+            }
+            _ => None,
+        },
+        _ => None,
     }
 }
 
@@ -588,4 +1639,1207 @@ mod test_item {
             }
         }
     }
+
+    mod encoded_len {
+        use crate::item::{encoded_len, to_writer, PgNumArray};
+
+        #[derive(serde::Serialize)]
+        struct Row {
+            i2: i16,
+            tx: String,
+            nl: Option<i64>,
+        }
+
+        #[test]
+        fn int() {
+            assert_eq!(8, encoded_len(&42i32).unwrap());
+        }
+
+        #[test]
+        fn text() {
+            assert_eq!(8, encoded_len(&"fuji").unwrap());
+        }
+
+        #[test]
+        fn null() {
+            let n: Option<i32> = None;
+            assert_eq!(4, encoded_len(&n).unwrap());
+        }
+
+        #[test]
+        fn array() {
+            let a: PgNumArray<i16> = PgNumArray(vec![3776, 599]);
+            assert_eq!(4 + 20 + 6 * 2, encoded_len(&a).unwrap());
+        }
+
+        #[test]
+        fn row() {
+            let r = Row {
+                i2: 42,
+                tx: "takao".into(),
+                nl: None,
+            };
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &r).unwrap();
+            assert_eq!(buf.len(), encoded_len(&r).unwrap());
+            assert_eq!(6 + 9 + 4, encoded_len(&r).unwrap());
+        }
+    }
+
+    mod limit {
+        use crate::config::Config;
+        use crate::item::{to_writer_with_config, Error};
+
+        #[derive(serde::Serialize)]
+        struct Inner {
+            tx: String,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Row {
+            id: i32,
+            inner: Inner,
+        }
+
+        fn row(tx: &str) -> Row {
+            Row {
+                id: 42,
+                inner: Inner { tx: tx.into() },
+            }
+        }
+
+        #[test]
+        fn field_ok() {
+            let cfg = Config::default().with_max_field_len(16);
+            let mut buf: Vec<u8> = vec![];
+            to_writer_with_config(&mut buf, &row("fuji"), &cfg).unwrap();
+            assert_eq!(8 + 4 + 16, buf.len());
+        }
+
+        #[test]
+        fn composite_too_large() {
+            let cfg = Config::default().with_max_field_len(16);
+            let mut buf: Vec<u8> = vec![];
+            let e = to_writer_with_config(&mut buf, &row("takao"), &cfg).unwrap_err();
+            match e {
+                Error::FieldTooLarge { field, size, max } => {
+                    assert_eq!("inner", field);
+                    assert_eq!(17, size);
+                    assert_eq!(16, max);
+                }
+                _ => panic!("unexpected error: {e}"),
+            }
+        }
+
+        #[test]
+        fn field_too_large() {
+            let cfg = Config::default().with_max_field_len(4);
+            let mut buf: Vec<u8> = vec![];
+            let e = to_writer_with_config(&mut buf, &row("takao"), &cfg).unwrap_err();
+            match e {
+                Error::FieldTooLarge { field, size, max } => {
+                    assert_eq!("inner.tx", field);
+                    assert_eq!(5, size);
+                    assert_eq!(4, max);
+                }
+                _ => panic!("unexpected error: {e}"),
+            }
+        }
+
+        #[test]
+        fn row_too_large() {
+            let cfg = Config::default().with_max_row_len(28);
+            let mut buf: Vec<u8> = vec![];
+            to_writer_with_config(&mut buf, &row("fuji"), &cfg).unwrap();
+            let e = to_writer_with_config(&mut buf, &row("takao"), &cfg).unwrap_err();
+            match e {
+                Error::RowTooLarge { field, size, max } => {
+                    assert_eq!("inner", field);
+                    assert_eq!(29, size);
+                    assert_eq!(28, max);
+                }
+                _ => panic!("unexpected error: {e}"),
+            }
+        }
+    }
+
+    mod nul {
+        use crate::blob::PgBlob;
+        use crate::config::{Config, NulPolicy};
+        use crate::item::{to_writer, to_writer_with_config, Error};
+
+        #[derive(serde::Serialize)]
+        struct Row {
+            tx: String,
+            ch: char,
+        }
+
+        fn row() -> Row {
+            Row {
+                tx: "fu\0ji".into(),
+                ch: 'a',
+            }
+        }
+
+        #[test]
+        fn error() {
+            let mut buf: Vec<u8> = vec![];
+            let e = to_writer(&mut buf, &row()).unwrap_err();
+            match e {
+                Error::NulInText { field } => assert_eq!("tx", field),
+                _ => panic!("unexpected error: {e}"),
+            }
+        }
+
+        #[test]
+        fn error_char() {
+            let r = Row {
+                tx: "fuji".into(),
+                ch: '\0',
+            };
+            let mut buf: Vec<u8> = vec![];
+            let e = to_writer(&mut buf, &r).unwrap_err();
+            match e {
+                Error::NulInText { field } => assert_eq!("ch", field),
+                _ => panic!("unexpected error: {e}"),
+            }
+        }
+
+        #[test]
+        fn strip() {
+            let cfg = Config::default().with_nul_policy(NulPolicy::Strip);
+            let mut buf: Vec<u8> = vec![];
+            to_writer_with_config(&mut buf, &row(), &cfg).unwrap();
+            assert_eq!(b"\0\0\0\x04fuji\0\0\0\x01a", buf.as_slice());
+        }
+
+        #[test]
+        fn replace() {
+            let cfg = Config::default().with_nul_policy(NulPolicy::Replace('?'));
+            let mut buf: Vec<u8> = vec![];
+            to_writer_with_config(&mut buf, &row(), &cfg).unwrap();
+            assert_eq!(b"\0\0\0\x05fu?ji\0\0\0\x01a", buf.as_slice());
+        }
+
+        #[test]
+        fn blob() {
+            let b: PgBlob = PgBlob::from(&b"fu\0ji"[..]);
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &b).unwrap();
+            assert_eq!(b"\0\0\0\x05fu\0ji", buf.as_slice());
+        }
+    }
+
+    mod human_readable {
+        use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4};
+
+        use crate::config::Config;
+        use crate::item::{to_writer, to_writer_with_config, Error};
+        use crate::registry::TypeRegistry;
+
+        #[derive(serde::Serialize)]
+        struct Row {
+            id: uuid::Uuid,
+            ip: IpAddr,
+        }
+
+        fn row() -> Row {
+            Row {
+                id: uuid::Uuid::from_u128(0xcafef00d_dead_beaf_face_864299792458),
+                ip: IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1)),
+            }
+        }
+
+        #[test]
+        fn compact() {
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &row()).unwrap();
+            let mut expected: Vec<u8> = vec![0, 0, 0, 16];
+            expected.extend_from_slice(&0xcafef00d_dead_beaf_face_864299792458u128.to_be_bytes());
+            expected.extend_from_slice(&[0, 0, 0, 8, 2, 32, 0, 4, 192, 168, 0, 1]);
+            assert_eq!(expected, buf);
+        }
+
+        #[test]
+        fn readable() {
+            let cfg = Config::default().with_human_readable(true);
+            let mut buf: Vec<u8> = vec![];
+            to_writer_with_config(&mut buf, &row(), &cfg).unwrap();
+            assert_eq!(&[0, 0, 0, 36], &buf[..4]);
+            assert_eq!(b"cafef00d-dead-beaf-face-864299792458", &buf[4..40]);
+            assert_eq!(b"\0\0\0\x0b192.168.0.1", &buf[40..]);
+        }
+
+        #[test]
+        fn ipv6() {
+            let ip: IpAddr = IpAddr::V6(Ipv6Addr::LOCALHOST);
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &ip).unwrap();
+            let mut expected: Vec<u8> = vec![0, 0, 0, 20, 3, 128, 0, 16];
+            expected.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+            assert_eq!(expected, buf);
+        }
+
+        #[test]
+        fn socket_addr() {
+            let sa: SocketAddr = "[::1]:5432".parse().unwrap();
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &sa).unwrap();
+            assert_eq!(b"\0\0\0\x0a[::1]:5432", buf.as_slice());
+        }
+
+        #[derive(serde::Serialize)]
+        struct Addr<T> {
+            addr: T,
+        }
+
+        fn octets(o: &[u8]) -> Vec<u8> {
+            let len: u8 = 4 + 10 * o.len() as u8;
+            let mut expected: Vec<u8> = vec![0, 0, 0, len, 0, 0, 0, o.len() as u8];
+            for b in o {
+                expected.extend_from_slice(&[0, 0, 0, 21, 0, 0, 0, 2, 0, *b]);
+            }
+            expected
+        }
+
+        #[test]
+        fn bare_ipv4() {
+            let a = Addr {
+                addr: Ipv4Addr::new(192, 168, 0, 1),
+            };
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &a).unwrap();
+            assert_eq!(octets(&[192, 168, 0, 1]), buf);
+        }
+
+        #[test]
+        fn bare_ipv6() {
+            let a = Addr {
+                addr: Ipv6Addr::LOCALHOST,
+            };
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &a).unwrap();
+            assert_eq!(octets(&Ipv6Addr::LOCALHOST.octets()), buf);
+        }
+
+        #[test]
+        fn bare_socket_addr_v4() {
+            let a = Addr {
+                addr: SocketAddrV4::new(Ipv4Addr::LOCALHOST, 5432),
+            };
+            let mut buf: Vec<u8> = vec![];
+            // (record of 4 int2s, int4): the inner record has no type
+            match to_writer(&mut buf, &a) {
+                Err(Error::UnknownType { field, typ }) => {
+                    assert_eq!("addr.0", field);
+                    assert_eq!("record", typ);
+                }
+                r => panic!("unexpected result: {r:?}"),
+            }
+        }
+
+        #[test]
+        fn u8_array() {
+            #[derive(serde::Serialize)]
+            struct Rgba {
+                h: [u8; 4],
+            }
+
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &Rgba { h: [1, 2, 3, 4] }).unwrap();
+            assert_eq!(octets(&[1, 2, 3, 4]), buf);
+        }
+
+        #[test]
+        fn bare_readable() {
+            let cfg = Config::default().with_human_readable(true);
+            let a = Addr {
+                addr: Ipv4Addr::new(192, 168, 0, 1),
+            };
+            let mut buf: Vec<u8> = vec![];
+            to_writer_with_config(&mut buf, &a, &cfg).unwrap();
+            assert_eq!(b"\0\0\0\x0b192.168.0.1", buf.as_slice());
+        }
+
+        #[test]
+        fn bare_declared() {
+            #[derive(serde::Serialize)]
+            #[serde(rename = "host")]
+            struct Host {
+                ip: Ipv4Addr,
+                net: Ipv4Addr,
+            }
+
+            let reg = TypeRegistry::default()
+                .with_composite("host", 16403, &["inet", "cidr"])
+                .unwrap();
+            let cfg = Config::default().with_registry(reg);
+            let h = Host {
+                ip: Ipv4Addr::new(192, 168, 0, 1),
+                net: Ipv4Addr::new(10, 0, 0, 0),
+            };
+            let mut buf: Vec<u8> = vec![];
+            to_writer_with_config(&mut buf, &Addr { addr: h }, &cfg).unwrap();
+            let expected: Vec<u8> = vec![
+                0, 0, 0, 36, 0, 0, 0, 2, // host: 2 fields
+                0, 0, 0x03, 0x65, 0, 0, 0, 8, 2, 32, 0, 4, 192, 168, 0, 1, // inet
+                0, 0, 0x02, 0x8a, 0, 0, 0, 8, 2, 32, 1, 4, 10, 0, 0, 0, // cidr
+            ];
+            assert_eq!(expected, buf);
+        }
+    }
+
+    #[cfg(feature = "encoding_rs")]
+    mod encoding {
+        use crate::config::Config;
+        use crate::encoding::{TextEncoding, UnmappablePolicy};
+        use crate::item::{to_writer_with_config, Error};
+
+        #[derive(serde::Serialize)]
+        struct Row {
+            tx: String,
+        }
+
+        #[test]
+        fn sjis() {
+            let enc = TextEncoding::new(encoding_rs::SHIFT_JIS, UnmappablePolicy::Error);
+            let cfg = Config::default().with_encoding(enc);
+            let r = Row {
+                tx: "富士".into()
+            };
+            let mut buf: Vec<u8> = vec![];
+            to_writer_with_config(&mut buf, &r, &cfg).unwrap();
+            assert_eq!(b"\0\0\0\x04\x95\x78\x8e\x6d", buf.as_slice());
+        }
+
+        #[test]
+        fn unmappable() {
+            let enc = TextEncoding::new(encoding_rs::WINDOWS_1252, UnmappablePolicy::Error);
+            let cfg = Config::default().with_encoding(enc);
+            let r = Row {
+                tx: "富士".into()
+            };
+            let mut buf: Vec<u8> = vec![];
+            let e = to_writer_with_config(&mut buf, &r, &cfg).unwrap_err();
+            match e {
+                Error::UnmappableChar { field, ch } => {
+                    assert_eq!("tx", field);
+                    assert_eq!('富', ch);
+                }
+                _ => panic!("unexpected error: {e}"),
+            }
+        }
+    }
+
+    mod composite {
+        use crate::config::Config;
+        use crate::item::{to_writer, to_writer_with_config, Error};
+        use crate::registry::TypeRegistry;
+        use crate::time::systemtime::Timestampz;
+
+        #[derive(serde::Serialize)]
+        #[serde(rename = "line_item")]
+        struct LineItem {
+            sku: String,
+            qty: Option<i32>,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Row {
+            id: i16,
+            item: LineItem,
+        }
+
+        fn row(qty: Option<i32>) -> Row {
+            Row {
+                id: 1,
+                item: LineItem {
+                    sku: "fuji".into(),
+                    qty,
+                },
+            }
+        }
+
+        fn registry() -> TypeRegistry {
+            TypeRegistry::default()
+                .with_composite("line_item", 16401, &["text", "int4"])
+                .unwrap()
+        }
+
+        #[test]
+        fn inferred() {
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &row(Some(3776))).unwrap();
+            let expected: Vec<u8> = vec![
+                0, 0, 0, 2, 0, 1, // id
+                0, 0, 0, 28, 0, 0, 0, 2, // item: 2 fields
+                0, 0, 0, 25, 0, 0, 0, 4, b'f', b'u', b'j', b'i', // text
+                0, 0, 0, 23, 0, 0, 0, 4, 0, 0, 0x0e, 0xc0, // int4
+            ];
+            assert_eq!(expected, buf);
+        }
+
+        #[test]
+        fn registered_null() {
+            let cfg = Config::default().with_registry(registry());
+            let mut buf: Vec<u8> = vec![];
+            to_writer_with_config(&mut buf, &row(None), &cfg).unwrap();
+            let expected: Vec<u8> = vec![
+                0, 0, 0, 2, 0, 1, // id
+                0, 0, 0, 24, 0, 0, 0, 2, // item: 2 fields
+                0, 0, 0, 25, 0, 0, 0, 4, b'f', b'u', b'j', b'i', // text
+                0, 0, 0, 23, 0xff, 0xff, 0xff, 0xff, // null int4
+            ];
+            assert_eq!(expected, buf);
+        }
+
+        #[test]
+        fn unregistered_null() {
+            let mut buf: Vec<u8> = vec![];
+            let e = to_writer(&mut buf, &row(None)).unwrap_err();
+            match e {
+                Error::Message(m) => assert!(m.contains("item.qty"), "{m}"),
+                _ => panic!("unexpected error: {e}"),
+            }
+        }
+
+        #[test]
+        fn field_count() {
+            #[derive(serde::Serialize)]
+            #[serde(rename = "line_item")]
+            struct Short {
+                sku: String,
+            }
+
+            #[derive(serde::Serialize)]
+            struct Row {
+                item: Short,
+            }
+
+            let cfg = Config::default().with_registry(registry());
+            let r = Row {
+                item: Short { sku: "fuji".into() },
+            };
+            let mut buf: Vec<u8> = vec![];
+            assert!(to_writer_with_config(&mut buf, &r, &cfg).is_err());
+        }
+
+        #[test]
+        fn nested() {
+            #[derive(serde::Serialize)]
+            struct Order {
+                at: Timestampz,
+                item: LineItem,
+            }
+
+            #[derive(serde::Serialize)]
+            struct Row {
+                order: Order,
+            }
+
+            let cfg = Config::default().with_registry(registry());
+            let r = Row {
+                order: Order {
+                    at: Timestampz::INFINITY,
+                    item: row(None).item,
+                },
+            };
+            let mut buf: Vec<u8> = vec![];
+            to_writer_with_config(&mut buf, &r, &cfg).unwrap();
+            let mut expected: Vec<u8> = vec![0, 0, 0, 52, 0, 0, 0, 2];
+            expected.extend_from_slice(&[0, 0, 0x04, 0xa0, 0, 0, 0, 8]); // timestamptz
+            expected.extend_from_slice(&i64::MAX.to_be_bytes());
+            expected.extend_from_slice(&[0, 0, 0x40, 0x11, 0, 0, 0, 24]); // line_item
+            expected.extend_from_slice(&[0, 0, 0, 2]);
+            expected.extend_from_slice(&[0, 0, 0, 25, 0, 0, 0, 4]);
+            expected.extend_from_slice(b"fuji");
+            expected.extend_from_slice(&[0, 0, 0, 23, 0xff, 0xff, 0xff, 0xff]);
+            assert_eq!(expected, buf);
+        }
+
+        #[test]
+        fn nested_unregistered() {
+            #[derive(serde::Serialize)]
+            struct Order {
+                item: LineItem,
+            }
+
+            #[derive(serde::Serialize)]
+            struct Row {
+                order: Order,
+            }
+
+            let r = Row {
+                order: Order {
+                    item: row(Some(1)).item,
+                },
+            };
+            let mut buf: Vec<u8> = vec![];
+            match to_writer(&mut buf, &r) {
+                Err(Error::UnknownType { field, typ }) => {
+                    assert_eq!("order.item", field);
+                    assert_eq!("line_item", typ);
+                }
+                r => panic!("unexpected result: {r:?}"),
+            }
+        }
+
+        #[test]
+        fn field_type() {
+            #[derive(serde::Serialize)]
+            #[serde(rename = "line_item")]
+            struct Wide {
+                sku: String,
+                qty: i64,
+            }
+
+            #[derive(serde::Serialize)]
+            struct Row {
+                item: Wide,
+            }
+
+            let r = Row {
+                item: Wide {
+                    sku: "fuji".into(),
+                    qty: 3776,
+                },
+            };
+            let cfg = Config::default().with_registry(registry());
+            let mut buf: Vec<u8> = vec![];
+            match to_writer_with_config(&mut buf, &r, &cfg) {
+                Err(Error::Message(m)) => assert!(m.contains("item.qty"), "{m}"),
+                r => panic!("unexpected result: {r:?}"),
+            }
+
+            // text types accept each other
+            let reg = TypeRegistry::default()
+                .with_composite("line_item", 16401, &["varchar", "int8"])
+                .unwrap();
+            let cfg = Config::default().with_registry(reg);
+            buf.clear();
+            to_writer_with_config(&mut buf, &r, &cfg).unwrap();
+            assert_eq!(&[0, 0, 0x04, 0x13], &buf[8..12]);
+        }
+    }
+
+    mod array {
+        use crate::config::Config;
+        use crate::item::{to_writer, to_writer_with_config, Error, PgNumArray, PgTextArray};
+        use crate::registry::TypeRegistry;
+
+        #[derive(serde::Serialize)]
+        #[serde(rename = "line_item")]
+        struct LineItem {
+            sku: String,
+            tags: Vec<String>,
+            qty: PgNumArray<i16>,
+        }
+
+        fn item(tags: Vec<String>) -> LineItem {
+            LineItem {
+                sku: "fuji".into(),
+                tags,
+                qty: PgNumArray(vec![7]),
+            }
+        }
+
+        fn registry() -> TypeRegistry {
+            TypeRegistry::default()
+                .with_composite("line_item", 16401, &["text", "text[]", "int2[]"])
+                .unwrap()
+        }
+
+        /// The expected line_item("fuji", ["a"] or [], [7]).
+        fn encoded_item(tag: bool) -> Vec<u8> {
+            let mut v: Vec<u8> = vec![0, 0, 0, 3];
+            v.extend_from_slice(&[0, 0, 0, 25, 0, 0, 0, 4]);
+            v.extend_from_slice(b"fuji");
+            match tag {
+                true => {
+                    v.extend_from_slice(&[0, 0, 0x03, 0xf1, 0, 0, 0, 25]);
+                    v.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 25]);
+                    v.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 1, b'a']);
+                }
+                false => {
+                    v.extend_from_slice(&[0, 0, 0x03, 0xf1, 0, 0, 0, 12]);
+                    v.extend_from_slice(&[0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 25]);
+                }
+            }
+            v.extend_from_slice(&[0, 0, 0x03, 0xed, 0, 0, 0, 26]);
+            v.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 21]);
+            v.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 2, 0, 7]);
+            v
+        }
+
+        #[test]
+        fn num() {
+            let a: PgNumArray<i32> = PgNumArray(vec![1, 2]);
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &a).unwrap();
+            let expected: Vec<u8> = vec![
+                0, 0, 0, 36, // size
+                0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 23, // ndim, flags, int4
+                0, 0, 0, 2, 0, 0, 0, 1, // dim, lbound
+                0, 0, 0, 4, 0, 0, 0, 1, 0, 0, 0, 4, 0, 0, 0, 2,
+            ];
+            assert_eq!(expected, buf);
+        }
+
+        #[test]
+        fn num_empty() {
+            let a: PgNumArray<f64> = PgNumArray(vec![]);
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &a).unwrap();
+            let expected: Vec<u8> = vec![0, 0, 0, 12, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x02, 0xbd];
+            assert_eq!(expected, buf);
+        }
+
+        #[test]
+        fn num_custom() {
+            use crate::item::PgNum;
+
+            /// A downstream number type(only the required items).
+            struct Code(i16);
+
+            impl PgNum for Code {
+                fn to_buf(&self, buf: &mut Vec<u8>) {
+                    buf.extend_from_slice(&self.0.to_be_bytes());
+                }
+                fn type_num() -> u32 {
+                    16500
+                }
+                fn size() -> usize {
+                    2
+                }
+            }
+
+            #[derive(serde::Serialize)]
+            struct Inner {
+                codes: PgNumArray<Code>,
+            }
+
+            #[derive(serde::Serialize)]
+            struct Row {
+                inner: Inner,
+            }
+
+            let r = Row {
+                inner: Inner {
+                    codes: PgNumArray(vec![Code(7)]),
+                },
+            };
+            let mut buf: Vec<u8> = vec![];
+            match to_writer(&mut buf, &r) {
+                Err(Error::UnknownType { typ, .. }) => assert_eq!("array of 16500", typ),
+                r => panic!("unexpected result: {r:?}"),
+            }
+
+            let reg = TypeRegistry::default()
+                .with_type("code", 16500)
+                .with_array("code", 16499)
+                .unwrap();
+            let cfg = Config::default().with_registry(reg);
+            buf.clear();
+            to_writer_with_config(&mut buf, &r, &cfg).unwrap();
+            let expected: Vec<u8> = vec![
+                0, 0, 0, 38, 0, 0, 0, 1, // inner: 1 field
+                0, 0, 0x40, 0x73, 0, 0, 0, 26, // code[]
+                0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0x40, 0x74, // ndim, flags, code
+                0, 0, 0, 1, 0, 0, 0, 1, // dim, lbound
+                0, 0, 0, 2, 0, 7,
+            ];
+            assert_eq!(expected, buf);
+        }
+
+        #[test]
+        fn text() {
+            let a: PgTextArray = PgTextArray(vec![Some("a".into()), None]);
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &a).unwrap();
+            let expected: Vec<u8> = vec![
+                0, 0, 0, 29, // size
+                0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 0, 25, // ndim, has null, text
+                0, 0, 0, 2, 0, 0, 0, 1, // dim, lbound
+                0, 0, 0, 1, b'a', 0xff, 0xff, 0xff, 0xff,
+            ];
+            assert_eq!(expected, buf);
+        }
+
+        #[test]
+        fn untyped_empty() {
+            let v: Vec<String> = vec![];
+            let mut buf: Vec<u8> = vec![];
+            assert!(to_writer(&mut buf, &v).is_err());
+            let t: PgTextArray = PgTextArray::from(v);
+            to_writer(&mut buf, &t).unwrap();
+            assert_eq!(
+                &[0, 0, 0, 12, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 25],
+                buf.as_slice()
+            );
+        }
+
+        #[test]
+        fn composites() {
+            let cfg = Config::default().with_registry(registry());
+            let mut buf: Vec<u8> = vec![];
+            to_writer_with_config(&mut buf, &vec![item(vec!["a".into()])], &cfg).unwrap();
+            let ie: Vec<u8> = encoded_item(true);
+            let mut expected: Vec<u8> = vec![];
+            expected.extend_from_slice(&(20 + 4 + ie.len() as i32).to_be_bytes());
+            expected.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0x40, 0x11]);
+            expected.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 1]);
+            expected.extend_from_slice(&(ie.len() as i32).to_be_bytes());
+            expected.extend_from_slice(&ie);
+            assert_eq!(expected, buf);
+        }
+
+        #[test]
+        fn declared_empty() {
+            #[derive(serde::Serialize)]
+            struct Row {
+                item: LineItem,
+            }
+
+            let cfg = Config::default().with_registry(registry());
+            let mut buf: Vec<u8> = vec![];
+            let r = Row { item: item(vec![]) };
+            to_writer_with_config(&mut buf, &r, &cfg).unwrap();
+            let ie: Vec<u8> = encoded_item(false);
+            assert_eq!(&(ie.len() as i32).to_be_bytes(), &buf[..4]);
+            assert_eq!(ie, buf[4..]);
+        }
+
+        #[test]
+        fn unregistered_elements() {
+            let mut buf: Vec<u8> = vec![];
+            let e = to_writer(&mut buf, &vec![item(vec!["a".into()])]).unwrap_err();
+            match e {
+                Error::UnknownType { typ, .. } => assert_eq!("line_item", typ),
+                _ => panic!("unexpected error: {e}"),
+            }
+        }
+
+        #[test]
+        fn multidimensional() {
+            let v: Vec<Vec<i32>> = vec![vec![1]];
+            let mut buf: Vec<u8> = vec![];
+            assert!(to_writer(&mut buf, &v).is_err());
+        }
+    }
+
+    mod map {
+        use std::collections::BTreeMap;
+
+        use crate::config::{Config, MapPolicy};
+        use crate::item::{to_writer, to_writer_with_config, Error};
+
+        #[derive(serde::Serialize)]
+        struct Row {
+            id: i16,
+            attrs: BTreeMap<String, String>,
+        }
+
+        fn row() -> Row {
+            Row {
+                id: 1,
+                attrs: BTreeMap::from([("k".into(), "v".into())]),
+            }
+        }
+
+        #[test]
+        fn rejected() {
+            let mut buf: Vec<u8> = vec![];
+            match to_writer(&mut buf, &row()) {
+                Err(Error::UnsupportedMap { field }) => assert_eq!("attrs", field),
+                r => panic!("unexpected result: {r:?}"),
+            }
+        }
+
+        #[test]
+        fn hstore() {
+            let cfg = Config::default().with_map_policy(MapPolicy::Hstore);
+            let mut buf: Vec<u8> = vec![];
+            to_writer_with_config(&mut buf, &row(), &cfg).unwrap();
+            let expected: Vec<u8> = vec![
+                0, 0, 0, 2, 0, 1, // id
+                0, 0, 0, 14, 0, 0, 0, 1, // attrs: 1 pair
+                0, 0, 0, 1, b'k', 0, 0, 0, 1, b'v',
+            ];
+            assert_eq!(expected, buf);
+        }
+
+        #[test]
+        fn hstore_non_string() {
+            #[derive(serde::Serialize)]
+            struct Row {
+                attrs: BTreeMap<String, i32>,
+            }
+
+            let r = Row {
+                attrs: BTreeMap::from([("k".into(), 1)]),
+            };
+            let cfg = Config::default().with_map_policy(MapPolicy::Hstore);
+            let mut buf: Vec<u8> = vec![];
+            assert!(to_writer_with_config(&mut buf, &r, &cfg).is_err());
+        }
+
+        #[cfg(feature = "serde_json")]
+        #[test]
+        fn jsonb() {
+            #[derive(serde::Serialize)]
+            struct Row {
+                attrs: BTreeMap<i32, Vec<bool>>,
+            }
+
+            let r = Row {
+                attrs: BTreeMap::from([(1, vec![true])]),
+            };
+            let cfg = Config::default().with_map_policy(MapPolicy::Jsonb);
+            let mut buf: Vec<u8> = vec![];
+            to_writer_with_config(&mut buf, &r, &cfg).unwrap();
+            let mut expected: Vec<u8> = vec![0, 0, 0, 13, 1];
+            expected.extend_from_slice(br#"{"1":[true]}"#);
+            assert_eq!(expected, buf);
+        }
+
+        #[test]
+        fn flatten() {
+            #[derive(serde::Serialize)]
+            struct Audit {
+                by: String,
+                rev: i32,
+            }
+
+            #[derive(serde::Serialize)]
+            struct Row {
+                id: i16,
+                #[serde(flatten)]
+                audit: Audit,
+            }
+
+            let r = Row {
+                id: 1,
+                audit: Audit {
+                    by: "a".into(),
+                    rev: 2,
+                },
+            };
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &r).unwrap();
+            let expected: Vec<u8> = vec![
+                0, 0, 0, 2, 0, 1, // id
+                0, 0, 0, 1, b'a', // by
+                0, 0, 0, 4, 0, 0, 0, 2, // rev
+            ];
+            assert_eq!(expected, buf);
+        }
+
+        #[test]
+        fn flatten_field_name() {
+            #[derive(serde::Serialize)]
+            struct Row {
+                id: i16,
+                #[serde(flatten)]
+                extra: BTreeMap<String, String>,
+            }
+
+            let r = Row {
+                id: 1,
+                extra: BTreeMap::from([("note".into(), "a\0b".into())]),
+            };
+            let mut buf: Vec<u8> = vec![];
+            match to_writer(&mut buf, &r) {
+                Err(Error::NulInText { field }) => assert_eq!("note", field),
+                r => panic!("unexpected result: {r:?}"),
+            }
+        }
+    }
+
+    mod variant {
+        use crate::config::{Config, EnumPolicy, LabelCase};
+        use crate::item::{to_writer, to_writer_with_config, Error};
+        use crate::registry::TypeRegistry;
+
+        #[derive(serde::Serialize)]
+        #[serde(rename = "shape")]
+        enum Shape {
+            #[allow(dead_code)]
+            Dot,
+            DarkSquare,
+            Circle(f64),
+            Rect(i16, i16),
+            Poly {
+                n: i16,
+            },
+        }
+
+        #[derive(serde::Serialize)]
+        struct Row {
+            shape: Shape,
+        }
+
+        fn write(shape: Shape, policy: EnumPolicy) -> Result<Vec<u8>, Error> {
+            let cfg = Config::default().with_enum_policy(policy);
+            let mut buf: Vec<u8> = vec![];
+            to_writer_with_config(&mut buf, &Row { shape }, &cfg)?;
+            Ok(buf)
+        }
+
+        #[test]
+        fn label() {
+            let mut buf: Vec<u8> = vec![];
+            to_writer(
+                &mut buf,
+                &Row {
+                    shape: Shape::DarkSquare,
+                },
+            )
+            .unwrap();
+            let mut expected: Vec<u8> = vec![0, 0, 0, 10];
+            expected.extend_from_slice(b"DarkSquare");
+            assert_eq!(expected, buf);
+        }
+
+        #[test]
+        fn label_case() {
+            let cases = [
+                (LabelCase::Lower, "darksquare"),
+                (LabelCase::Upper, "DARKSQUARE"),
+                (LabelCase::Snake, "dark_square"),
+                (LabelCase::ScreamingSnake, "DARK_SQUARE"),
+                (LabelCase::Kebab, "dark-square"),
+                (LabelCase::Camel, "darkSquare"),
+            ];
+            for (case, label) in cases {
+                let buf: Vec<u8> = write(Shape::DarkSquare, EnumPolicy::Label(case)).unwrap();
+                assert_eq!(label.as_bytes(), &buf[4..]);
+            }
+        }
+
+        #[test]
+        fn label_registered() {
+            let reg = TypeRegistry::default()
+                .with_type("shape", 16500)
+                .with_array("shape", 16499)
+                .unwrap();
+            let cfg = Config::default().with_registry(reg);
+            let mut buf: Vec<u8> = vec![];
+            to_writer_with_config(&mut buf, &vec![Shape::DarkSquare], &cfg).unwrap();
+            assert_eq!(16500u32.to_be_bytes(), buf[12..16]);
+        }
+
+        #[test]
+        fn ordinal() {
+            let buf: Vec<u8> = write(Shape::DarkSquare, EnumPolicy::Int2).unwrap();
+            assert_eq!(vec![0, 0, 0, 2, 0, 1], buf);
+            let buf: Vec<u8> = write(Shape::DarkSquare, EnumPolicy::Int4).unwrap();
+            assert_eq!(vec![0, 0, 0, 4, 0, 0, 0, 1], buf);
+        }
+
+        #[test]
+        fn data_rejected() {
+            let shapes = [Shape::Circle(1.0), Shape::Rect(1, 2), Shape::Poly { n: 3 }];
+            for shape in shapes {
+                match write(shape, EnumPolicy::Int2) {
+                    Err(Error::UnsupportedVariant { field, variant }) => {
+                        assert_eq!("shape", field);
+                        assert!(variant.starts_with("shape::"));
+                    }
+                    r => panic!("unexpected result: {r:?}"),
+                }
+            }
+        }
+
+        #[cfg(feature = "serde_json")]
+        #[test]
+        fn jsonb() {
+            let cases = [
+                (Shape::DarkSquare, r#""DarkSquare""#),
+                (Shape::Circle(1.5), r#"{"Circle":1.5}"#),
+                (Shape::Rect(1, 2), r#"{"Rect":[1,2]}"#),
+                (Shape::Poly { n: 3 }, r#"{"Poly":{"n":3}}"#),
+            ];
+            for (shape, json) in cases {
+                let buf: Vec<u8> = write(shape, EnumPolicy::Jsonb).unwrap();
+                assert_eq!(json.len() as u8 + 1, buf[3]);
+                assert_eq!(1, buf[4]);
+                assert_eq!(json.as_bytes(), &buf[5..]);
+            }
+        }
+    }
+
+    mod tuple {
+        use crate::config::Config;
+        use crate::item::{to_writer, to_writer_with_config, Error};
+        use crate::registry::TypeRegistry;
+
+        #[derive(serde::Serialize)]
+        struct Pair(i16, i16);
+
+        #[test]
+        fn row() {
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &(3776i32, "fuji", None::<f64>)).unwrap();
+            let expected: Vec<u8> = vec![
+                0, 0, 0, 4, 0, 0, 0x0e, 0xc0, // int4
+                0, 0, 0, 4, b'f', b'u', b'j', b'i', // text
+                0xff, 0xff, 0xff, 0xff, // null
+            ];
+            assert_eq!(expected, buf);
+        }
+
+        #[test]
+        fn tuple_struct_row() {
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &Pair(1, 2)).unwrap();
+            assert_eq!(vec![0, 0, 0, 2, 0, 1, 0, 0, 0, 2, 0, 2], buf);
+        }
+
+        #[test]
+        fn nested() {
+            #[derive(serde::Serialize)]
+            struct Row {
+                id: i16,
+                pair: (i16, bool),
+            }
+
+            let mut buf: Vec<u8> = vec![];
+            to_writer(
+                &mut buf,
+                &Row {
+                    id: 1,
+                    pair: (2, true),
+                },
+            )
+            .unwrap();
+            let expected: Vec<u8> = vec![
+                0, 0, 0, 2, 0, 1, // id
+                0, 0, 0, 23, 0, 0, 0, 2, // pair: 2 fields
+                0, 0, 0, 21, 0, 0, 0, 2, 0, 2, // int2
+                0, 0, 0, 16, 0, 0, 0, 1, 1, // bool
+            ];
+            assert_eq!(expected, buf);
+        }
+
+        #[test]
+        fn registered() {
+            #[derive(serde::Serialize)]
+            #[serde(rename = "pair")]
+            struct Pair(i16, Option<i16>);
+
+            let reg = TypeRegistry::default()
+                .with_composite("pair", 16402, &["int2", "int2"])
+                .unwrap()
+                .with_array("pair", 16401)
+                .unwrap();
+            let cfg = Config::default().with_registry(reg);
+            let mut buf: Vec<u8> = vec![];
+            to_writer_with_config(&mut buf, &(1i16, vec![Pair(2, None)]), &cfg).unwrap();
+            assert_eq!(16402u32.to_be_bytes(), buf[18..22]);
+        }
+
+        #[derive(serde::Serialize)]
+        #[serde(rename = "vec3")]
+        struct Vec3 {
+            xyz: [i32; 3],
+        }
+
+        #[derive(serde::Serialize)]
+        struct Row3 {
+            v: Vec3,
+        }
+
+        fn row3(xyz: [i32; 3]) -> Row3 {
+            Row3 { v: Vec3 { xyz } }
+        }
+
+        #[test]
+        fn fixed_array_record() {
+            #[derive(serde::Serialize)]
+            struct Row {
+                xyz: [i32; 3],
+            }
+
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &Row { xyz: [1, 2, 3] }).unwrap();
+            let expected: Vec<u8> = vec![
+                0, 0, 0, 40, 0, 0, 0, 3, // xyz: a record of 3 fields
+                0, 0, 0, 23, 0, 0, 0, 4, 0, 0, 0, 1, // int4
+                0, 0, 0, 23, 0, 0, 0, 4, 0, 0, 0, 2, // int4
+                0, 0, 0, 23, 0, 0, 0, 4, 0, 0, 0, 3, // int4
+            ];
+            assert_eq!(expected, buf);
+
+            // a field of a composite value needs the type(see fixed_array_declared)
+            buf.clear();
+            match to_writer(&mut buf, &row3([1, 2, 3])) {
+                Err(Error::UnknownType { field, typ }) => {
+                    assert_eq!("v.xyz", field);
+                    assert_eq!("record", typ);
+                }
+                r => panic!("unexpected result: {r:?}"),
+            }
+        }
+
+        #[test]
+        fn fixed_array_declared() {
+            let reg = TypeRegistry::default()
+                .with_composite("vec3", 16404, &["int4[]"])
+                .unwrap();
+            let cfg = Config::default().with_registry(reg);
+            let mut buf: Vec<u8> = vec![];
+            to_writer_with_config(&mut buf, &row3([1, 2, 3]), &cfg).unwrap();
+            let expected: Vec<u8> = vec![
+                0, 0, 0, 56, 0, 0, 0, 1, // v: 1 field
+                0, 0, 0x03, 0xef, 0, 0, 0, 44, // int4[]
+                0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0, 23, // ndim, flags, int4
+                0, 0, 0, 3, 0, 0, 0, 1, // dim, lbound
+                0, 0, 0, 4, 0, 0, 0, 1, 0, 0, 0, 4, 0, 0, 0, 2, 0, 0, 0, 4, 0, 0, 0, 3,
+            ];
+            assert_eq!(expected, buf);
+        }
+
+        #[test]
+        fn fixed_array_mismatch() {
+            #[derive(serde::Serialize)]
+            #[serde(rename = "vec3")]
+            struct Vec3 {
+                xyz: (i32, String, i32),
+            }
+
+            let reg = TypeRegistry::default()
+                .with_composite("vec3", 16404, &["int4[]"])
+                .unwrap();
+            let cfg = Config::default().with_registry(reg);
+            let v = Vec3 {
+                xyz: (1, "2".into(), 3),
+            };
+            let mut buf: Vec<u8> = vec![];
+            match to_writer_with_config(&mut buf, &(v,), &cfg) {
+                Err(Error::Message(m)) => assert!(m.contains("different types"), "{m}"),
+                r => panic!("unexpected result: {r:?}"),
+            }
+        }
+
+        #[test]
+        fn field_name() {
+            #[derive(serde::Serialize)]
+            struct Row {
+                pair: (i16, String),
+            }
+
+            let mut buf: Vec<u8> = vec![];
+            match to_writer(&mut buf, &(1i16, "a\0b")) {
+                Err(Error::NulInText { field }) => assert_eq!("1", field),
+                r => panic!("unexpected result: {r:?}"),
+            }
+            let r = Row {
+                pair: (1, "a\0b".into()),
+            };
+            match to_writer(&mut buf, &r) {
+                Err(Error::NulInText { field }) => assert_eq!("pair.1", field),
+                r => panic!("unexpected result: {r:?}"),
+            }
+        }
+    }
 }
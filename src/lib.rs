@@ -1,5 +1,6 @@
 #![forbid(clippy::unwrap_used)]
 
+pub mod config;
 pub mod header;
 pub mod item;
 pub mod trailer;
@@ -8,3 +9,17 @@ pub mod time;
 pub mod uuid;
 
 pub mod blob;
+pub mod geometric;
+pub mod hstore;
+pub mod net;
+pub mod range;
+pub mod registry;
+pub mod vector;
+
+mod capture;
+
+#[cfg(feature = "encoding_rs")]
+pub mod encoding;
+
+#[cfg(feature = "postgis")]
+pub mod postgis;
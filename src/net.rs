@@ -0,0 +1,283 @@
+//! Network address structs(inet, cidr, macaddr, macaddr8).
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use serde::{Serialize, Serializer};
+
+use crate::registry::{type_hint, Raw};
+
+/// PGSQL_AF_INET
+const FAMILY_INET: u8 = 2;
+
+/// PGSQL_AF_INET6
+const FAMILY_INET6: u8 = 3;
+
+/// The number of bits of an address.
+fn max_bits(addr: &IpAddr) -> u8 {
+    match addr {
+        IpAddr::V4(_) => 32,
+        IpAddr::V6(_) => 128,
+    }
+}
+
+/// Encodes an address(family, bits, is_cidr, size, address).
+fn to_buf(addr: &IpAddr, bits: u8, is_cidr: bool) -> Vec<u8> {
+    let mut buf: Vec<u8> = Vec::with_capacity(20);
+    match addr {
+        IpAddr::V4(a) => {
+            buf.extend_from_slice(&[FAMILY_INET, bits, is_cidr.into(), 4]);
+            buf.extend_from_slice(&a.octets());
+        }
+        IpAddr::V6(a) => {
+            buf.extend_from_slice(&[FAMILY_INET6, bits, is_cidr.into(), 16]);
+            buf.extend_from_slice(&a.octets());
+        }
+    }
+    buf
+}
+
+/// An inet(an address with an optional netmask)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PgInet {
+    addr: IpAddr,
+    bits: u8,
+}
+
+impl PgInet {
+    /// Creates an inet(e.g, 192.168.0.1/24).
+    pub fn new(addr: IpAddr, bits: u8) -> Result<Self, String> {
+        match bits <= max_bits(&addr) {
+            true => Ok(Self { addr, bits }),
+            false => Err(format!("invalid netmask: {addr}/{bits}")),
+        }
+    }
+
+    /// The address.
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// The netmask length.
+    pub fn bits(&self) -> u8 {
+        self.bits
+    }
+}
+
+impl Serialize for PgInet {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ser.serialize_newtype_struct(
+            type_hint!("inet"),
+            &Raw(&to_buf(&self.addr, self.bits, false)),
+        )
+    }
+}
+
+/// Creates a host address(/32 or /128)
+impl From<IpAddr> for PgInet {
+    fn from(addr: IpAddr) -> Self {
+        let bits: u8 = max_bits(&addr);
+        Self { addr, bits }
+    }
+}
+
+impl From<Ipv4Addr> for PgInet {
+    fn from(a: Ipv4Addr) -> Self {
+        IpAddr::V4(a).into()
+    }
+}
+
+impl From<Ipv6Addr> for PgInet {
+    fn from(a: Ipv6Addr) -> Self {
+        IpAddr::V6(a).into()
+    }
+}
+
+impl From<[u8; 4]> for PgInet {
+    fn from(o: [u8; 4]) -> Self {
+        Ipv4Addr::from(o).into()
+    }
+}
+
+impl From<[u8; 16]> for PgInet {
+    fn from(o: [u8; 16]) -> Self {
+        Ipv6Addr::from(o).into()
+    }
+}
+
+/// A cidr(a network address without host bits)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PgCidr {
+    addr: IpAddr,
+    bits: u8,
+}
+
+impl PgCidr {
+    /// Creates a cidr(e.g, 192.168.0.0/24).
+    ///
+    /// The bits right of the netmask must be zero.
+    pub fn new(addr: IpAddr, bits: u8) -> Result<Self, String> {
+        let host_bits_zero: bool = match addr {
+            IpAddr::V4(a) => u32::from(a).checked_shl(bits.into()).unwrap_or(0) == 0,
+            IpAddr::V6(a) => u128::from(a).checked_shl(bits.into()).unwrap_or(0) == 0,
+        };
+        match (bits <= max_bits(&addr), host_bits_zero) {
+            (false, _) => Err(format!("invalid netmask: {addr}/{bits}")),
+            (_, false) => Err(format!("host bits set: {addr}/{bits}")),
+            _ => Ok(Self { addr, bits }),
+        }
+    }
+
+    /// The address.
+    pub fn addr(&self) -> IpAddr {
+        self.addr
+    }
+
+    /// The netmask length.
+    pub fn bits(&self) -> u8 {
+        self.bits
+    }
+}
+
+impl Serialize for PgCidr {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ser.serialize_newtype_struct(
+            type_hint!("cidr"),
+            &Raw(&to_buf(&self.addr, self.bits, true)),
+        )
+    }
+}
+
+/// Creates a host network(/32 or /128)
+impl From<IpAddr> for PgCidr {
+    fn from(addr: IpAddr) -> Self {
+        let bits: u8 = max_bits(&addr);
+        Self { addr, bits }
+    }
+}
+
+impl From<Ipv4Addr> for PgCidr {
+    fn from(a: Ipv4Addr) -> Self {
+        IpAddr::V4(a).into()
+    }
+}
+
+impl From<Ipv6Addr> for PgCidr {
+    fn from(a: Ipv6Addr) -> Self {
+        IpAddr::V6(a).into()
+    }
+}
+
+/// A macaddr(6 bytes)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PgMacAddr(pub [u8; 6]);
+
+impl Serialize for PgMacAddr {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ser.serialize_newtype_struct(type_hint!("macaddr"), &Raw(&self.0))
+    }
+}
+
+impl From<[u8; 6]> for PgMacAddr {
+    fn from(b: [u8; 6]) -> Self {
+        Self(b)
+    }
+}
+
+/// A macaddr8(8 bytes)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PgMacAddr8(pub [u8; 8]);
+
+impl Serialize for PgMacAddr8 {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ser.serialize_newtype_struct(type_hint!("macaddr8"), &Raw(&self.0))
+    }
+}
+
+impl From<[u8; 8]> for PgMacAddr8 {
+    fn from(b: [u8; 8]) -> Self {
+        Self(b)
+    }
+}
+
+#[cfg(test)]
+mod test_net {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    use crate::item::to_writer;
+    use crate::net::{PgCidr, PgInet, PgMacAddr, PgMacAddr8};
+
+    fn encode<T>(val: &T) -> Vec<u8>
+    where
+        T: serde::Serialize,
+    {
+        let mut buf: Vec<u8> = vec![];
+        to_writer(&mut buf, val).unwrap();
+        buf
+    }
+
+    #[test]
+    fn inet4() {
+        let i: PgInet = PgInet::new(Ipv4Addr::new(192, 168, 0, 1).into(), 24).unwrap();
+        let expected: Vec<u8> = vec![0, 0, 0, 8, 2, 24, 0, 4, 192, 168, 0, 1];
+        assert_eq!(expected, encode(&i));
+    }
+
+    #[test]
+    fn inet6() {
+        let i: PgInet = Ipv6Addr::LOCALHOST.into();
+        let mut expected: Vec<u8> = vec![0, 0, 0, 20, 3, 128, 0, 16];
+        expected.extend_from_slice(&Ipv6Addr::LOCALHOST.octets());
+        assert_eq!(expected, encode(&i));
+    }
+
+    #[test]
+    fn inet_octets() {
+        let i: PgInet = [10, 0, 0, 1].into();
+        assert_eq!(IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1)), i.addr());
+        assert_eq!(32, i.bits());
+    }
+
+    #[test]
+    fn inet_invalid() {
+        assert!(PgInet::new(Ipv4Addr::LOCALHOST.into(), 33).is_err());
+        assert!(PgInet::new(Ipv6Addr::LOCALHOST.into(), 128).is_ok());
+    }
+
+    #[test]
+    fn cidr() {
+        let c: PgCidr = PgCidr::new(Ipv4Addr::new(192, 168, 0, 0).into(), 16).unwrap();
+        let expected: Vec<u8> = vec![0, 0, 0, 8, 2, 16, 1, 4, 192, 168, 0, 0];
+        assert_eq!(expected, encode(&c));
+    }
+
+    #[test]
+    fn cidr_host_bits() {
+        assert!(PgCidr::new(Ipv4Addr::new(192, 168, 0, 1).into(), 24).is_err());
+        assert!(PgCidr::new(Ipv4Addr::new(192, 168, 0, 1).into(), 32).is_ok());
+        assert!(PgCidr::new(Ipv4Addr::UNSPECIFIED.into(), 0).is_ok());
+        let net6: Ipv6Addr = "2001:db8::".parse().unwrap();
+        assert!(PgCidr::new(net6.into(), 32).is_ok());
+        let host6: Ipv6Addr = "2001:db8::1".parse().unwrap();
+        assert!(PgCidr::new(host6.into(), 64).is_err());
+    }
+
+    #[test]
+    fn macaddr() {
+        let m: PgMacAddr = [0x08, 0x00, 0x2b, 0x01, 0x02, 0x03].into();
+        assert_eq!(vec![0, 0, 0, 6, 0x08, 0, 0x2b, 1, 2, 3], encode(&m));
+        let m8: PgMacAddr8 = [0x08, 0x00, 0x2b, 0x01, 0x02, 0x03, 0x04, 0x05].into();
+        assert_eq!(vec![0, 0, 0, 8, 0x08, 0, 0x2b, 1, 2, 3, 4, 5], encode(&m8));
+    }
+}
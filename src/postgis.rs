@@ -0,0 +1,356 @@
+//! PostGIS geometry/geography structs written as EWKB.
+//!
+//! Geometries are encoded in little endian with the SRID flag if a SRID is set.
+//! The OIDs of extension types are database-specific; register them to use
+//! geometries in composite values or arrays(see [`crate::registry::TypeRegistry`]).
+
+use serde::ser::Error;
+use serde::{Serialize, Serializer};
+
+use crate::registry::{type_hint, Raw};
+
+/// wkbNDR(little endian)
+const WKB_NDR: u8 = 1;
+
+/// The flag of the EWKB type indicating a SRID follows.
+const EWKB_SRID_FLAG: u32 = 0x2000_0000;
+
+/// The SRID of WGS 84(the default of geography).
+pub const SRID_WGS84: u32 = 4326;
+
+/// A 2D geometry.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Geometry {
+    /// A point(x, y); (NaN, NaN) is an empty point.
+    Point((f64, f64)),
+    LineString(Vec<(f64, f64)>),
+
+    /// Rings(the exterior ring first) of closed points.
+    Polygon(Vec<Vec<(f64, f64)>>),
+
+    MultiPoint(Vec<(f64, f64)>),
+    MultiLineString(Vec<Vec<(f64, f64)>>),
+    MultiPolygon(Vec<Vec<Vec<(f64, f64)>>>),
+    GeometryCollection(Vec<Geometry>),
+}
+
+fn put_u32(buf: &mut Vec<u8>, u: u32) {
+    buf.extend_from_slice(&u.to_le_bytes());
+}
+
+fn put_len(buf: &mut Vec<u8>, len: usize) -> Result<(), String> {
+    let u: u32 = u32::try_from(len).map_err(|_| format!("too many items: {len}"))?;
+    put_u32(buf, u);
+    Ok(())
+}
+
+fn put_points(buf: &mut Vec<u8>, pts: &[(f64, f64)]) -> Result<(), String> {
+    put_len(buf, pts.len())?;
+    for (x, y) in pts {
+        buf.extend_from_slice(&x.to_le_bytes());
+        buf.extend_from_slice(&y.to_le_bytes());
+    }
+    Ok(())
+}
+
+fn check_line(pts: &[(f64, f64)]) -> Result<(), String> {
+    match pts.len() {
+        1 => Err("a linestring must have at least 2 points".into()),
+        _ => Ok(()),
+    }
+}
+
+fn check_ring(ring: &[(f64, f64)]) -> Result<(), String> {
+    if ring.len() < 4 {
+        return Err("a polygon ring must have at least 4 points".into());
+    }
+    match ring.first() == ring.last() {
+        true => Ok(()),
+        false => Err("a polygon ring must be closed".into()),
+    }
+}
+
+fn put_rings(buf: &mut Vec<u8>, rings: &[Vec<(f64, f64)>]) -> Result<(), String> {
+    put_len(buf, rings.len())?;
+    for ring in rings {
+        check_ring(ring)?;
+        put_points(buf, ring)?;
+    }
+    Ok(())
+}
+
+impl Geometry {
+    /// The WKB type number.
+    fn type_num(&self) -> u32 {
+        match self {
+            Self::Point(_) => 1,
+            Self::LineString(_) => 2,
+            Self::Polygon(_) => 3,
+            Self::MultiPoint(_) => 4,
+            Self::MultiLineString(_) => 5,
+            Self::MultiPolygon(_) => 6,
+            Self::GeometryCollection(_) => 7,
+        }
+    }
+
+    /// Extends a buf by the EWKB(the SRID is written for the outermost geometry only).
+    fn to_buf(&self, buf: &mut Vec<u8>, srid: Option<u32>) -> Result<(), String> {
+        buf.push(WKB_NDR);
+        match srid {
+            None => put_u32(buf, self.type_num()),
+            Some(s) => {
+                put_u32(buf, self.type_num() | EWKB_SRID_FLAG);
+                put_u32(buf, s);
+            }
+        }
+        match self {
+            Self::Point((x, y)) => {
+                buf.extend_from_slice(&x.to_le_bytes());
+                buf.extend_from_slice(&y.to_le_bytes());
+            }
+            Self::LineString(pts) => {
+                check_line(pts)?;
+                put_points(buf, pts)?;
+            }
+            Self::Polygon(rings) => put_rings(buf, rings)?,
+            Self::MultiPoint(pts) => {
+                put_len(buf, pts.len())?;
+                for p in pts {
+                    Self::Point(*p).to_buf(buf, None)?;
+                }
+            }
+            Self::MultiLineString(lines) => {
+                put_len(buf, lines.len())?;
+                for l in lines {
+                    buf.push(WKB_NDR);
+                    put_u32(buf, 2);
+                    check_line(l)?;
+                    put_points(buf, l)?;
+                }
+            }
+            Self::MultiPolygon(polys) => {
+                put_len(buf, polys.len())?;
+                for p in polys {
+                    buf.push(WKB_NDR);
+                    put_u32(buf, 3);
+                    put_rings(buf, p)?;
+                }
+            }
+            Self::GeometryCollection(geoms) => {
+                put_len(buf, geoms.len())?;
+                for g in geoms {
+                    g.to_buf(buf, None)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Encodes as EWKB.
+    pub fn to_ewkb(&self, srid: Option<u32>) -> Result<Vec<u8>, String> {
+        let mut buf: Vec<u8> = Vec::with_capacity(64);
+        self.to_buf(&mut buf, srid)?;
+        Ok(buf)
+    }
+}
+
+/// A geometry with an optional SRID(e.g, geometry(Point, 4326))
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgGeometry {
+    geom: Geometry,
+    srid: Option<u32>,
+}
+
+impl PgGeometry {
+    /// Sets the SRID.
+    pub fn with_srid(mut self, srid: u32) -> Self {
+        self.srid = Some(srid);
+        self
+    }
+
+    pub fn geometry(&self) -> &Geometry {
+        &self.geom
+    }
+
+    pub fn srid(&self) -> Option<u32> {
+        self.srid
+    }
+}
+
+/// Creates a geometry without a SRID.
+impl From<Geometry> for PgGeometry {
+    fn from(geom: Geometry) -> Self {
+        Self { geom, srid: None }
+    }
+}
+
+impl Serialize for PgGeometry {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let buf: Vec<u8> = self.geom.to_ewkb(self.srid).map_err(S::Error::custom)?;
+        ser.serialize_newtype_struct(type_hint!("geometry"), &Raw(&buf))
+    }
+}
+
+/// A geography(longitude/latitude, WGS 84 by default)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgGeography {
+    geom: Geometry,
+    srid: u32,
+}
+
+impl PgGeography {
+    /// Sets the SRID(a geodetic one).
+    pub fn with_srid(mut self, srid: u32) -> Self {
+        self.srid = srid;
+        self
+    }
+
+    pub fn geometry(&self) -> &Geometry {
+        &self.geom
+    }
+
+    pub fn srid(&self) -> u32 {
+        self.srid
+    }
+}
+
+/// Creates a geography in WGS 84.
+impl From<Geometry> for PgGeography {
+    fn from(geom: Geometry) -> Self {
+        Self {
+            geom,
+            srid: SRID_WGS84,
+        }
+    }
+}
+
+impl Serialize for PgGeography {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let buf: Vec<u8> = self
+            .geom
+            .to_ewkb(Some(self.srid))
+            .map_err(S::Error::custom)?;
+        ser.serialize_newtype_struct(type_hint!("geography"), &Raw(&buf))
+    }
+}
+
+/// Pre-built WKB/EWKB bytes of a geometry written as is.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgWkb(pub Vec<u8>);
+
+impl From<Vec<u8>> for PgWkb {
+    fn from(v: Vec<u8>) -> Self {
+        Self(v)
+    }
+}
+
+impl From<&[u8]> for PgWkb {
+    fn from(s: &[u8]) -> Self {
+        Self(s.into())
+    }
+}
+
+impl Serialize for PgWkb {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ser.serialize_newtype_struct(type_hint!("geometry"), &Raw(&self.0))
+    }
+}
+
+#[cfg(test)]
+mod test_postgis {
+    use crate::item::to_writer;
+    use crate::postgis::{Geometry, PgGeography, PgGeometry, PgWkb};
+
+    /// The hex of an EWKB.
+    fn hex(b: &[u8]) -> String {
+        b.iter().map(|u| format!("{u:02X}")).collect()
+    }
+
+    #[test]
+    fn point() {
+        let g = Geometry::Point((1.0, 2.0));
+        // ST_AsBinary('POINT(1 2)')
+        assert_eq!(
+            "0101000000000000000000F03F0000000000000040",
+            hex(&g.to_ewkb(None).unwrap())
+        );
+        // ST_AsEWKB('SRID=4326;POINT(1 2)')
+        assert_eq!(
+            "0101000020E6100000000000000000F03F0000000000000040",
+            hex(&g.to_ewkb(Some(4326)).unwrap())
+        );
+    }
+
+    #[test]
+    fn linestring() {
+        let g = Geometry::LineString(vec![(0.0, 0.0), (1.0, 1.0)]);
+        assert_eq!(
+            "01020000000200000000000000000000000000000000000000000000000000F03F000000000000F03F",
+            hex(&g.to_ewkb(None).unwrap())
+        );
+        assert!(Geometry::LineString(vec![(0.0, 0.0)])
+            .to_ewkb(None)
+            .is_err());
+    }
+
+    #[test]
+    fn polygon() {
+        let ring = vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 0.0)];
+        let g = Geometry::Polygon(vec![ring.clone()]);
+        let b: Vec<u8> = g.to_ewkb(None).unwrap();
+        assert_eq!("01030000000100000004000000", hex(&b[..13]));
+        assert_eq!(13 + 4 * 16, b.len());
+
+        let open = vec![(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+        assert!(Geometry::Polygon(vec![open]).to_ewkb(None).is_err());
+
+        let m = Geometry::MultiPolygon(vec![vec![ring]]);
+        let mb: Vec<u8> = m.to_ewkb(Some(4326)).unwrap();
+        assert_eq!("0106000020E610000001000000", hex(&mb[..13]));
+        assert_eq!(b, mb[13..]);
+    }
+
+    #[test]
+    fn multi() {
+        let g = Geometry::MultiPoint(vec![(1.0, 2.0)]);
+        assert_eq!(
+            "0104000000010000000101000000000000000000F03F0000000000000040",
+            hex(&g.to_ewkb(None).unwrap())
+        );
+        let c = Geometry::GeometryCollection(vec![Geometry::Point((1.0, 2.0))]);
+        assert_eq!(
+            "0107000000010000000101000000000000000000F03F0000000000000040",
+            hex(&c.to_ewkb(None).unwrap())
+        );
+    }
+
+    #[test]
+    fn serialize() {
+        let g: PgGeometry = PgGeometry::from(Geometry::Point((1.0, 2.0))).with_srid(4326);
+        let mut buf: Vec<u8> = vec![];
+        to_writer(&mut buf, &g).unwrap();
+        assert_eq!(
+            "000000190101000020E6100000000000000000F03F0000000000000040",
+            hex(&buf)
+        );
+
+        let geog: PgGeography = PgGeography::from(Geometry::Point((1.0, 2.0)));
+        let mut gbuf: Vec<u8> = vec![];
+        to_writer(&mut gbuf, &geog).unwrap();
+        assert_eq!(buf, gbuf);
+
+        let w: PgWkb = PgWkb::from(&buf[4..]);
+        let mut wbuf: Vec<u8> = vec![];
+        to_writer(&mut wbuf, &w).unwrap();
+        assert_eq!(buf, wbuf);
+    }
+}
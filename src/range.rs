@@ -0,0 +1,371 @@
+//! Range and multirange structs(e.g, int8range, tstzrange, int4multirange).
+
+use core::ops::{Bound, Range, RangeFrom, RangeFull, RangeInclusive, RangeTo, RangeToInclusive};
+
+use serde::ser::Error;
+use serde::{Serialize, Serializer};
+
+use crate::item::to_writer;
+use crate::registry::{type_hint, Raw};
+use crate::time::date::PgDate;
+use crate::time::primitive::Timestamp;
+use crate::time::systemtime::Timestampz;
+
+const RANGE_EMPTY: u8 = 0x01;
+const RANGE_LB_INC: u8 = 0x02;
+const RANGE_UB_INC: u8 = 0x04;
+const RANGE_LB_INF: u8 = 0x08;
+const RANGE_UB_INF: u8 = 0x10;
+
+/// A type which has a built-in range type.
+///
+/// - i32 => int4range
+/// - i64 => int8range
+/// - [`PgDate`] => daterange
+/// - [`Timestamp`] => tsrange
+/// - [`Timestampz`] => tstzrange
+///
+/// PostgreSQL canonicalizes discrete ranges(e.g, `[1,2]` becomes `[1,3)`) on receive.
+///
+/// numrange is unsupported: this crate has no numeric type, whose binary format
+/// is a sequence of base-10000 digits(not a float8).
+pub trait PgRangeBound: Serialize {
+    /// The type hint of the range type.
+    #[doc(hidden)]
+    const RANGE: &'static str;
+
+    /// The type hint of the multirange type.
+    #[doc(hidden)]
+    const MULTIRANGE: &'static str;
+}
+
+macro_rules! range_bound {
+    ($typ: ty, $range: literal, $multirange: literal) => {
+        impl PgRangeBound for $typ {
+            const RANGE: &'static str = type_hint!($range);
+            const MULTIRANGE: &'static str = type_hint!($multirange);
+        }
+    };
+}
+
+range_bound!(i32, "int4range", "int4multirange");
+range_bound!(i64, "int8range", "int8multirange");
+range_bound!(PgDate, "daterange", "datemultirange");
+range_bound!(Timestamp, "tsrange", "tsmultirange");
+range_bound!(Timestampz, "tstzrange", "tstzmultirange");
+
+/// A range of postgresql(e.g, [1,10), (,now()], empty)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgRange<T> {
+    lower: Bound<T>,
+    upper: Bound<T>,
+    empty: bool,
+}
+
+impl<T> PgRange<T>
+where
+    T: PartialOrd,
+{
+    /// Creates a range from the bounds.
+    ///
+    /// Equal bounds are an empty range unless both are inclusive(e.g, [1,1)),
+    /// as normalised by PostgreSQL.
+    ///
+    /// Returns an error if the lower bound is greater than the upper bound.
+    pub fn new(lower: Bound<T>, upper: Bound<T>) -> Result<Self, String> {
+        let (l, linc) = match &lower {
+            Bound::Included(l) => (l, true),
+            Bound::Excluded(l) => (l, false),
+            Bound::Unbounded => return Ok(Self::bounds(lower, upper)),
+        };
+        let (u, uinc) = match &upper {
+            Bound::Included(u) => (u, true),
+            Bound::Excluded(u) => (u, false),
+            Bound::Unbounded => return Ok(Self::bounds(lower, upper)),
+        };
+        if l > u {
+            return Err(String::from(
+                "range lower bound must be less than or equal to range upper bound",
+            ));
+        }
+        match (l == u, linc && uinc) {
+            (true, false) => Ok(Self::empty()),
+            _ => Ok(Self::bounds(lower, upper)),
+        }
+    }
+}
+
+impl<T> PgRange<T> {
+    /// Creates a range from the bounds without checking them.
+    fn bounds(lower: Bound<T>, upper: Bound<T>) -> Self {
+        Self {
+            lower,
+            upper,
+            empty: false,
+        }
+    }
+
+    /// Creates an empty range.
+    pub fn empty() -> Self {
+        Self {
+            lower: Bound::Unbounded,
+            upper: Bound::Unbounded,
+            empty: true,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.empty
+    }
+
+    pub fn lower(&self) -> Bound<&T> {
+        self.lower.as_ref()
+    }
+
+    pub fn upper(&self) -> Bound<&T> {
+        self.upper.as_ref()
+    }
+}
+
+impl<T> PgRange<T>
+where
+    T: PgRangeBound,
+{
+    /// Extends a buf by the flags and the length-prefixed bounds.
+    fn to_buf(&self, buf: &mut Vec<u8>) -> Result<(), crate::item::Error> {
+        if self.empty {
+            buf.push(RANGE_EMPTY);
+            return Ok(());
+        }
+        let lf: u8 = match self.lower {
+            Bound::Included(_) => RANGE_LB_INC,
+            Bound::Excluded(_) => 0,
+            Bound::Unbounded => RANGE_LB_INF,
+        };
+        let uf: u8 = match self.upper {
+            Bound::Included(_) => RANGE_UB_INC,
+            Bound::Excluded(_) => 0,
+            Bound::Unbounded => RANGE_UB_INF,
+        };
+        buf.push(lf | uf);
+        for b in [&self.lower, &self.upper] {
+            if let Bound::Included(v) | Bound::Excluded(v) = b {
+                to_writer(&mut *buf, v)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T> Serialize for PgRange<T>
+where
+    T: PgRangeBound,
+{
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut buf: Vec<u8> = Vec::with_capacity(25);
+        self.to_buf(&mut buf)
+            .map_err(|e| S::Error::custom(format!("unable to write a range: {e}")))?;
+        ser.serialize_newtype_struct(T::RANGE, &Raw(&buf))
+    }
+}
+
+/// [start, end)(empty if start >= end, as in Rust)
+impl<T> From<Range<T>> for PgRange<T>
+where
+    T: PartialOrd,
+{
+    fn from(r: Range<T>) -> Self {
+        Self::new(Bound::Included(r.start), Bound::Excluded(r.end))
+            .unwrap_or_else(|_| Self::empty())
+    }
+}
+
+/// [start, end](empty if start > end, as in Rust)
+impl<T> From<RangeInclusive<T>> for PgRange<T>
+where
+    T: PartialOrd,
+{
+    fn from(r: RangeInclusive<T>) -> Self {
+        let (start, end) = r.into_inner();
+        Self::new(Bound::Included(start), Bound::Included(end)).unwrap_or_else(|_| Self::empty())
+    }
+}
+
+/// [start, )
+impl<T> From<RangeFrom<T>> for PgRange<T> {
+    fn from(r: RangeFrom<T>) -> Self {
+        Self::bounds(Bound::Included(r.start), Bound::Unbounded)
+    }
+}
+
+/// (, end)
+impl<T> From<RangeTo<T>> for PgRange<T> {
+    fn from(r: RangeTo<T>) -> Self {
+        Self::bounds(Bound::Unbounded, Bound::Excluded(r.end))
+    }
+}
+
+/// (, end]
+impl<T> From<RangeToInclusive<T>> for PgRange<T> {
+    fn from(r: RangeToInclusive<T>) -> Self {
+        Self::bounds(Bound::Unbounded, Bound::Included(r.end))
+    }
+}
+
+/// (, )
+impl<T> From<RangeFull> for PgRange<T> {
+    fn from(_: RangeFull) -> Self {
+        Self::bounds(Bound::Unbounded, Bound::Unbounded)
+    }
+}
+
+/// A multirange of postgresql(PostgreSQL 14+)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PgMultirange<T>(pub Vec<PgRange<T>>);
+
+impl<T> From<Vec<PgRange<T>>> for PgMultirange<T> {
+    fn from(v: Vec<PgRange<T>>) -> Self {
+        Self(v)
+    }
+}
+
+impl<T> Serialize for PgMultirange<T>
+where
+    T: PgRangeBound,
+{
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let cnt: i32 = i32::try_from(self.0.len())
+            .map_err(|_| S::Error::custom(format!("too many ranges: {}", self.0.len())))?;
+        let mut buf: Vec<u8> = Vec::with_capacity(4 + 29 * self.0.len());
+        buf.extend_from_slice(&cnt.to_be_bytes());
+        let mut rbuf: Vec<u8> = Vec::with_capacity(25);
+        for r in &self.0 {
+            rbuf.clear();
+            r.to_buf(&mut rbuf)
+                .map_err(|e| S::Error::custom(format!("unable to write a range: {e}")))?;
+            // a range is much smaller than i32::MAX
+            let sz: i32 = rbuf.len() as i32;
+            buf.extend_from_slice(&sz.to_be_bytes());
+            buf.extend_from_slice(&rbuf);
+        }
+        ser.serialize_newtype_struct(T::MULTIRANGE, &Raw(&buf))
+    }
+}
+
+#[cfg(test)]
+mod test_range {
+    use core::ops::Bound;
+
+    use crate::item::to_writer;
+    use crate::range::{PgMultirange, PgRange};
+    use crate::time::date::PgDate;
+    use crate::time::systemtime::Timestampz;
+
+    fn encode<T>(val: &T) -> Vec<u8>
+    where
+        T: serde::Serialize,
+    {
+        let mut buf: Vec<u8> = vec![];
+        to_writer(&mut buf, val).unwrap();
+        buf
+    }
+
+    #[test]
+    fn int4range() {
+        let r: PgRange<i32> = (1..10).into();
+        let expected: Vec<u8> = vec![
+            0, 0, 0, 17, 0x02, 0, 0, 0, 4, 0, 0, 0, 1, 0, 0, 0, 4, 0, 0, 0, 10,
+        ];
+        assert_eq!(expected, encode(&r));
+    }
+
+    #[test]
+    fn int8range_inclusive() {
+        let r: PgRange<i64> = (1..=2).into();
+        let mut expected: Vec<u8> = vec![0, 0, 0, 25, 0x06, 0, 0, 0, 8];
+        expected.extend_from_slice(&1i64.to_be_bytes());
+        expected.extend_from_slice(&[0, 0, 0, 8]);
+        expected.extend_from_slice(&2i64.to_be_bytes());
+        assert_eq!(expected, encode(&r));
+    }
+
+    #[test]
+    fn unbounded() {
+        let r: PgRange<i32> = (..=5).into();
+        let expected: Vec<u8> = vec![0, 0, 0, 9, 0x0c, 0, 0, 0, 4, 0, 0, 0, 5];
+        assert_eq!(expected, encode(&r));
+        let f: PgRange<i32> = (..).into();
+        assert_eq!(vec![0, 0, 0, 1, 0x18], encode(&f));
+    }
+
+    #[test]
+    fn exclusive_lower() {
+        let r: PgRange<i32> = PgRange::new(Bound::Excluded(0), Bound::Unbounded).unwrap();
+        let expected: Vec<u8> = vec![0, 0, 0, 9, 0x10, 0, 0, 0, 4, 0, 0, 0, 0];
+        assert_eq!(expected, encode(&r));
+    }
+
+    #[test]
+    fn reversed() {
+        assert!(PgRange::new(Bound::Included(2), Bound::Excluded(1)).is_err());
+        assert!(PgRange::new(Bound::Excluded(2), Bound::Included(1)).is_err());
+        let d = PgRange::new(Bound::Included(PgDate::INFINITY), Bound::Unbounded);
+        assert!(d.is_ok());
+        let t = PgRange::new(
+            Bound::Included(Timestampz::INFINITY),
+            Bound::Included(Timestampz::NEG_INFINITY),
+        );
+        assert!(t.is_err());
+    }
+
+    #[test]
+    fn reversed_std() {
+        #[allow(clippy::reversed_empty_ranges)]
+        let r: PgRange<i32> = (2..1).into();
+        assert!(r.is_empty());
+        let r: PgRange<i32> = (1..1).into();
+        assert!(r.is_empty());
+        let r: PgRange<i32> = (1..=1).into();
+        assert!(!r.is_empty());
+    }
+
+    #[test]
+    fn equal_bounds() {
+        let r: PgRange<i32> = PgRange::new(Bound::Included(1), Bound::Excluded(1)).unwrap();
+        assert!(r.is_empty());
+        let r: PgRange<i32> = PgRange::new(Bound::Excluded(1), Bound::Excluded(1)).unwrap();
+        assert!(r.is_empty());
+        let r: PgRange<i32> = PgRange::new(Bound::Included(1), Bound::Included(1)).unwrap();
+        assert_eq!(Bound::Included(&1), r.lower());
+    }
+
+    #[test]
+    fn empty() {
+        let r: PgRange<i32> = PgRange::empty();
+        assert!(r.is_empty());
+        assert_eq!(vec![0, 0, 0, 1, 0x01], encode(&r));
+    }
+
+    #[test]
+    fn tstzrange() {
+        let r: PgRange<Timestampz> = (Timestampz::NEG_INFINITY..).into();
+        let expected: Vec<u8> = vec![0, 0, 0, 13, 0x12, 0, 0, 0, 8, 0x80, 0, 0, 0, 0, 0, 0, 0];
+        assert_eq!(expected, encode(&r));
+    }
+
+    #[test]
+    fn multirange() {
+        let m: PgMultirange<i32> = PgMultirange(vec![(1..2).into(), PgRange::empty()]);
+        let expected: Vec<u8> = vec![
+            0, 0, 0, 30, 0, 0, 0, 2, 0, 0, 0, 17, 0x02, 0, 0, 0, 4, 0, 0, 0, 1, 0, 0, 0, 4, 0, 0,
+            0, 2, 0, 0, 0, 1, 0x01,
+        ];
+        assert_eq!(expected, encode(&m));
+    }
+}
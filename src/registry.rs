@@ -0,0 +1,510 @@
+//! Type names and OIDs used by composite(record) values and arrays.
+//!
+//! Records and arrays embed OIDs. OIDs of built-in types are fixed, but
+//! user-defined types(composites, enums, domains, extensions) have
+//! database-specific OIDs which must be registered or loaded from a dump of
+//! `pg_type`.
+
+use std::collections::HashMap;
+use std::io;
+
+use serde::{Serialize, Serializer};
+
+/// The prefix of the newtype struct names used as type hints.
+pub(crate) const TYPE_HINT_PREFIX: &str = "$row2pgcopy::";
+
+/// Creates the name of a newtype struct hinting a postgresql type.
+macro_rules! type_hint {
+    ($typname: literal) => {
+        concat!("$row2pgcopy::", $typname)
+    };
+}
+
+pub(crate) use type_hint;
+
+/// The OID of the pseudo type record(an anonymous composite).
+pub const RECORD_OID: u32 = 2249;
+
+/// Built-in types(typname, oid, oid of the array type).
+const BUILTIN: &[(&str, u32, u32)] = &[
+    ("bool", 16, 1000),
+    ("bytea", 17, 1001),
+    ("int8", 20, 1016),
+    ("int2", 21, 1005),
+    ("int4", 23, 1007),
+    ("text", 25, 1009),
+    ("json", 114, 199),
+    ("point", 600, 1017),
+    ("lseg", 601, 1018),
+    ("path", 602, 1019),
+    ("box", 603, 1020),
+    ("polygon", 604, 1027),
+    ("line", 628, 629),
+    ("cidr", 650, 651),
+    ("float4", 700, 1021),
+    ("float8", 701, 1022),
+    ("circle", 718, 719),
+    ("macaddr8", 774, 775),
+    ("macaddr", 829, 1040),
+    ("inet", 869, 1041),
+    ("bpchar", 1042, 1014),
+    ("varchar", 1043, 1015),
+    ("date", 1082, 1182),
+    ("time", 1083, 1183),
+    ("timestamp", 1114, 1115),
+    ("timestamptz", 1184, 1185),
+    ("interval", 1186, 1187),
+    ("timetz", 1266, 1270),
+    ("numeric", 1700, 1231),
+    ("record", RECORD_OID, 2287),
+    ("uuid", 2950, 2951),
+    ("jsonb", 3802, 3807),
+    ("int4range", 3904, 3905),
+    ("numrange", 3906, 3907),
+    ("tsrange", 3908, 3909),
+    ("tstzrange", 3910, 3911),
+    ("daterange", 3912, 3913),
+    ("int8range", 3926, 3927),
+    ("int4multirange", 4451, 6150),
+    ("nummultirange", 4532, 6151),
+    ("tsmultirange", 4533, 6152),
+    ("tstzmultirange", 4534, 6153),
+    ("datemultirange", 4535, 6155),
+    ("int8multirange", 4536, 6157),
+];
+
+/// The OID of a built-in type(e.g, "int4" -> 23).
+pub fn builtin_oid(typname: &str) -> Option<u32> {
+    BUILTIN
+        .iter()
+        .find(|(name, _, _)| *name == typname)
+        .map(|(_, oid, _)| *oid)
+}
+
+/// Built-in types sharing the binary format of a text.
+const TEXT_TYPES: &[&str] = &["text", "varchar", "bpchar", "json"];
+
+/// Whether an OID is of a built-in type or its array type.
+pub(crate) fn is_builtin(oid: u32) -> bool {
+    BUILTIN.iter().any(|(_, o, a)| *o == oid || *a == oid)
+}
+
+/// Whether an OID is of a built-in type sent as a text(e.g, varchar).
+pub(crate) fn is_text(oid: u32) -> bool {
+    TEXT_TYPES.iter().any(|t| builtin_oid(t) == Some(oid))
+}
+
+/// OIDs of user-defined types.
+///
+/// A nested struct is looked up by its serde name; use `#[serde(rename = "...")]`
+/// to match the name of the composite type.
+///
+/// ```
+/// use row2pgcopy::registry::TypeRegistry;
+///
+/// #[derive(serde::Serialize)]
+/// #[serde(rename = "line_item")]
+/// struct LineItem {
+///     sku: String,
+///     qty: Option<i32>,
+/// }
+///
+/// let reg = TypeRegistry::default()
+///     .with_composite("line_item", 16401, &["text", "int4"])
+///     .unwrap();
+/// assert_eq!(Some(16401), reg.oid("line_item"));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct TypeRegistry {
+    types: HashMap<String, u32>,
+    composites: HashMap<String, Vec<u32>>,
+
+    /// OIDs of array types keyed by the OIDs of their elements.
+    arrays: HashMap<u32, u32>,
+}
+
+impl TypeRegistry {
+    /// Registers a type(e.g, an enum, a domain or an extension type).
+    pub fn with_type(mut self, typname: &str, oid: u32) -> Self {
+        self.types.insert(typname.into(), oid);
+        self
+    }
+
+    /// Registers the array type of a registered or built-in type(e.g, line_item[]).
+    pub fn with_array(mut self, typname: &str, array_oid: u32) -> Result<Self, String> {
+        let elem: u32 = self
+            .oid(typname)
+            .ok_or_else(|| format!("unknown element type: {typname}"))?;
+        self.arrays.insert(elem, array_oid);
+        Ok(self)
+    }
+
+    /// Registers a composite type and the type names of its fields(in order).
+    ///
+    /// Array fields are named like "text[]" or "_text".
+    /// Fails if a field type is neither built-in nor registered.
+    pub fn with_composite<N>(
+        mut self,
+        typname: &str,
+        oid: u32,
+        fields: &[N],
+    ) -> Result<Self, String>
+    where
+        N: AsRef<str>,
+    {
+        let oids: Vec<u32> = fields
+            .iter()
+            .map(|f| {
+                let f: &str = f.as_ref();
+                self.oid(f)
+                    .ok_or_else(|| format!("unknown field type: {f}"))
+            })
+            .collect::<Result<_, _>>()?;
+        self.types.insert(typname.into(), oid);
+        self.composites.insert(typname.into(), oids);
+        Ok(self)
+    }
+
+    /// Registers the types of a CSV dump of `pg_type`.
+    ///
+    /// The header names the columns; oid and typname are required, typarray
+    /// is optional and other columns are ignored. Each row must be a single
+    /// line(quoted fields spanning lines are unsupported).
+    ///
+    /// Types are keyed by typname only: a name with different OIDs(e.g, the
+    /// same enum in two schemas) is an error, so filter the schemas in the dump.
+    ///
+    /// ```sql
+    /// COPY (
+    ///   SELECT t.oid, t.typname, t.typarray FROM pg_type t
+    ///   JOIN pg_namespace n ON n.oid = t.typnamespace
+    ///   WHERE n.nspname IN ('pg_catalog', 'public')
+    /// ) TO STDOUT WITH (FORMAT csv, HEADER)
+    /// ```
+    pub fn with_pg_type_csv<R>(mut self, mut rdr: R) -> Result<Self, String>
+    where
+        R: io::Read,
+    {
+        let mut s: String = String::new();
+        rdr.read_to_string(&mut s)
+            .map_err(|e| format!("unable to read a csv: {e}"))?;
+        let mut lines = s.lines().filter(|l| !l.is_empty());
+        let header: Vec<String> = csv_fields(lines.next().unwrap_or_default())?;
+        let col = |name: &str| header.iter().position(|h| h == name);
+        let oid_col: usize = col("oid").ok_or("no oid column")?;
+        let name_col: usize = col("typname").ok_or("no typname column")?;
+        let array_col: Option<usize> = col("typarray");
+        for line in lines {
+            let fields: Vec<String> = csv_fields(line)?;
+            let get = |i: usize| {
+                fields
+                    .get(i)
+                    .map(|f| f.as_str())
+                    .ok_or_else(|| format!("too few columns: {line}"))
+            };
+            let oid: u32 = parse_oid(get(oid_col)?)?;
+            let array_oid: u32 = match array_col {
+                Some(i) => parse_oid(get(i)?)?,
+                None => 0,
+            };
+            self.insert_pg_type(get(name_col)?, oid, array_oid)?;
+        }
+        Ok(self)
+    }
+
+    /// Registers the types of a JSON dump of `pg_type`(an array of objects).
+    ///
+    /// OIDs may be numbers or strings; typarray is optional. As with
+    /// [`Self::with_pg_type_csv`], a typname with different OIDs is an error.
+    ///
+    /// ```sql
+    /// SELECT json_agg(json_build_object('oid', oid, 'typname', typname, 'typarray', typarray)) FROM pg_type
+    /// ```
+    #[cfg(feature = "serde_json")]
+    pub fn with_pg_type_json<R>(mut self, rdr: R) -> Result<Self, String>
+    where
+        R: io::Read,
+    {
+        use serde_json::Value;
+
+        let rows: Vec<Value> =
+            serde_json::from_reader(rdr).map_err(|e| format!("invalid json: {e}"))?;
+        let oid = |row: &Value, key: &str| -> Result<u32, String> {
+            match row.get(key) {
+                None | Some(Value::Null) => Ok(0),
+                Some(Value::Number(n)) => n
+                    .as_u64()
+                    .and_then(|u| u32::try_from(u).ok())
+                    .ok_or_else(|| format!("invalid oid: {n}")),
+                Some(Value::String(s)) => parse_oid(s),
+                Some(v) => Err(format!("invalid oid: {v}")),
+            }
+        };
+        for row in &rows {
+            let name: &str = row
+                .get("typname")
+                .and_then(|v| v.as_str())
+                .ok_or_else(|| format!("no typname: {row}"))?;
+            let o: u32 = oid(row, "oid")?;
+            if 0 == o {
+                return Err(format!("no oid: {row}"));
+            }
+            self.insert_pg_type(name, o, oid(row, "typarray")?)?;
+        }
+        Ok(self)
+    }
+
+    /// Registers a row of `pg_type`(typarray is 0 if there is no array type).
+    ///
+    /// Rejects a registered typname with a different OID.
+    fn insert_pg_type(&mut self, typname: &str, oid: u32, array_oid: u32) -> Result<(), String> {
+        match self.types.get(typname) {
+            Some(o) if *o != oid => {
+                return Err(format!(
+                    "duplicate type {typname}: {o}, {oid}(filter the schemas)"
+                ))
+            }
+            _ => {}
+        }
+        self.types.insert(typname.into(), oid);
+        if 0 != array_oid {
+            self.arrays.insert(oid, array_oid);
+        }
+        Ok(())
+    }
+
+    /// The OID of a registered or built-in type.
+    ///
+    /// Array types can be named like "text[]" or "_text".
+    pub fn oid(&self, typname: &str) -> Option<u32> {
+        let direct: Option<u32> = self
+            .types
+            .get(typname)
+            .copied()
+            .or_else(|| builtin_oid(typname));
+        if direct.is_some() {
+            return direct;
+        }
+        typname
+            .strip_suffix("[]")
+            .or_else(|| typname.strip_prefix('_'))
+            .and_then(|elem| self.oid(elem))
+            .and_then(|elem| self.array_oid(elem))
+    }
+
+    /// The OID of the array type of an element type.
+    pub fn array_oid(&self, elem_oid: u32) -> Option<u32> {
+        self.arrays.get(&elem_oid).copied().or_else(|| {
+            BUILTIN
+                .iter()
+                .find(|(_, oid, _)| *oid == elem_oid)
+                .map(|(_, _, arr)| *arr)
+        })
+    }
+
+    /// The OID of the element type of an array type.
+    pub fn elem_oid(&self, array_oid: u32) -> Option<u32> {
+        self.arrays
+            .iter()
+            .find(|(_, arr)| **arr == array_oid)
+            .map(|(elem, _)| *elem)
+            .or_else(|| {
+                BUILTIN
+                    .iter()
+                    .find(|(_, _, arr)| *arr == array_oid)
+                    .map(|(_, oid, _)| *oid)
+            })
+    }
+
+    /// The field OIDs of a registered composite type.
+    pub(crate) fn composite(&self, typname: &str) -> Option<&[u32]> {
+        self.composites.get(typname).map(|v| v.as_slice())
+    }
+}
+
+fn parse_oid(s: &str) -> Result<u32, String> {
+    s.trim()
+        .parse()
+        .map_err(|e| format!("invalid oid({s}): {e}"))
+}
+
+/// Splits a line of a CSV into fields(quoted fields may contain commas and "").
+///
+/// Quoted fields containing line breaks are unsupported.
+fn csv_fields(line: &str) -> Result<Vec<String>, String> {
+    let mut fields: Vec<String> = vec![];
+    let mut field: String = String::new();
+    let mut quoted: bool = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (false, '"') => quoted = true,
+            (false, ',') => fields.push(core::mem::take(&mut field)),
+            (_, c) => field.push(c),
+        }
+    }
+    if quoted {
+        return Err(format!("unterminated quote: {line}"));
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+/// Bytes written as a value of the hinted type.
+pub(crate) struct Raw<'a>(pub(crate) &'a [u8]);
+
+impl Serialize for Raw<'_> {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ser.serialize_bytes(self.0)
+    }
+}
+
+#[cfg(test)]
+mod test_registry {
+    use crate::registry::{builtin_oid, TypeRegistry};
+
+    #[test]
+    fn builtin() {
+        assert_eq!(Some(23), builtin_oid("int4"));
+        assert_eq!(Some(1184), builtin_oid("timestamptz"));
+        assert_eq!(None, builtin_oid("line_item"));
+    }
+
+    #[test]
+    fn registered() {
+        let reg = TypeRegistry::default().with_type("mood", 16390);
+        assert_eq!(Some(16390), reg.oid("mood"));
+        assert_eq!(Some(25), reg.oid("text"));
+    }
+
+    #[test]
+    fn composite() {
+        let reg = TypeRegistry::default()
+            .with_type("mood", 16390)
+            .with_composite("line_item", 16401, &["text", "mood"])
+            .unwrap();
+        assert_eq!(Some(16401), reg.oid("line_item"));
+        assert_eq!(Some(&[25, 16390][..]), reg.composite("line_item"));
+    }
+
+    #[test]
+    fn array() {
+        let reg = TypeRegistry::default()
+            .with_composite("line_item", 16401, &["text[]", "_int4"])
+            .unwrap()
+            .with_array("line_item", 16400)
+            .unwrap();
+        assert_eq!(Some(&[1009, 1007][..]), reg.composite("line_item"));
+        assert_eq!(Some(16400), reg.oid("line_item[]"));
+        assert_eq!(Some(16400), reg.oid("_line_item"));
+        assert_eq!(Some(16401), reg.elem_oid(16400));
+        assert_eq!(Some(25), reg.elem_oid(1009));
+        assert_eq!(None, reg.oid("mood[]"));
+    }
+
+    #[test]
+    fn csv() {
+        let dump: &[u8] = b"oid,typname,typarray\r\n16390,mood,16389\r\n16389,_mood,0\r\n16395,\"my \"\"type\"\"\",0\r\n";
+        let reg = TypeRegistry::default().with_pg_type_csv(dump).unwrap();
+        assert_eq!(Some(16390), reg.oid("mood"));
+        assert_eq!(Some(16389), reg.oid("mood[]"));
+        assert_eq!(Some(16390), reg.elem_oid(16389));
+        assert_eq!(Some(16395), reg.oid("my \"type\""));
+        assert_eq!(Some(23), reg.oid("int4"));
+    }
+
+    #[test]
+    fn csv_columns() {
+        let dump: &[u8] = b"typname,typtype,oid\nvector,b,16500\n";
+        let reg = TypeRegistry::default().with_pg_type_csv(dump).unwrap();
+        assert_eq!(Some(16500), reg.oid("vector"));
+        assert_eq!(None, reg.oid("vector[]"));
+
+        let e = TypeRegistry::default()
+            .with_pg_type_csv(&b"typname\nvector\n"[..])
+            .unwrap_err();
+        assert_eq!("no oid column", e);
+    }
+
+    #[test]
+    fn csv_duplicate() {
+        let dump: &[u8] = b"oid,typname\n16390,status\n16490,status\n";
+        let e = TypeRegistry::default().with_pg_type_csv(dump).unwrap_err();
+        assert!(e.starts_with("duplicate type status: 16390, 16490"), "{e}");
+
+        let dump: &[u8] = b"oid,typname\n16390,status\n16390,status\n";
+        let reg = TypeRegistry::default().with_pg_type_csv(dump).unwrap();
+        assert_eq!(Some(16390), reg.oid("status"));
+    }
+
+    #[cfg(feature = "serde_json")]
+    #[test]
+    fn json() {
+        let dump: &[u8] = br#"[
+            {"oid": 16390, "typname": "mood", "typarray": 16389},
+            {"oid": "16500", "typname": "vector"}
+        ]"#;
+        let reg = TypeRegistry::default().with_pg_type_json(dump).unwrap();
+        assert_eq!(Some(16390), reg.oid("mood"));
+        assert_eq!(Some(16389), reg.oid("_mood"));
+        assert_eq!(Some(16500), reg.oid("vector"));
+    }
+
+    #[test]
+    fn extension_type_in_composite() {
+        use crate::config::Config;
+        use crate::item::{to_writer, to_writer_with_config, Error};
+        use crate::vector::PgVector;
+
+        #[derive(serde::Serialize)]
+        struct Chunk {
+            embedding: PgVector,
+        }
+
+        #[derive(serde::Serialize)]
+        struct Row {
+            chunk: Chunk,
+        }
+
+        let r = Row {
+            chunk: Chunk {
+                embedding: PgVector(vec![1.0]),
+            },
+        };
+        let mut buf: Vec<u8> = vec![];
+        match to_writer(&mut buf, &r) {
+            Err(Error::UnknownType { field, typ }) => {
+                assert_eq!("chunk.embedding", field);
+                assert_eq!("vector", typ);
+            }
+            r => panic!("unexpected result: {r:?}"),
+        }
+
+        let reg = TypeRegistry::default().with_type("vector", 16500);
+        let cfg = Config::default().with_registry(reg);
+        buf.clear();
+        to_writer_with_config(&mut buf, &r, &cfg).unwrap();
+        let expected: Vec<u8> = vec![
+            0, 0, 0, 20, 0, 0, 0, 1, // chunk: 1 field
+            0, 0, 0x40, 0x74, 0, 0, 0, 8, // vector
+            0, 1, 0, 0, 0x3f, 0x80, 0, 0, // dim, unused, 1.0
+        ];
+        assert_eq!(expected, buf);
+    }
+
+    #[test]
+    fn unknown_field() {
+        let e = TypeRegistry::default()
+            .with_composite("line_item", 16401, &["text", "mood"])
+            .unwrap_err();
+        assert_eq!("unknown field type: mood", e);
+    }
+}
@@ -1,6 +1,154 @@
 //! time related modules.
 
+use core::fmt;
+
 pub mod systemtime;
 
-#[cfg(feature = "time_primitive")]
 pub mod primitive;
+
+pub mod date;
+pub mod interval;
+pub mod timeofday;
+
+/// Microseconds between the unix epoch and the PostgreSQL epoch(2000-01-01).
+pub(crate) const PG_EPOCH_UNIX_MICROS: i128 = 946_684_800_000_000;
+
+/// The smallest timestamp accepted by PostgreSQL(4714-11-24 00:00:00 BC, proleptic Gregorian).
+pub const MIN_TIMESTAMP_MICROS: i64 = -211_813_488_000_000_000;
+
+/// The first timestamp rejected by PostgreSQL(294277-01-01 00:00:00 AD).
+pub const END_TIMESTAMP_MICROS: i64 = 9_223_371_331_200_000_000;
+
+/// A bound of the PostgreSQL timestamp range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimestampBound {
+    /// The value is before 4713 BC.
+    Min,
+
+    /// The value is after 294276 AD.
+    Max,
+}
+
+/// A timestamp outside of the range supported by PostgreSQL.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimestampRangeError {
+    /// The value in microseconds since 2000-01-01 00:00:00.
+    pub micros: i128,
+
+    /// The exceeded bound.
+    pub bound: TimestampBound,
+}
+
+impl fmt::Display for TimestampRangeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> Result<(), fmt::Error> {
+        let m: i128 = self.micros;
+        match self.bound {
+            TimestampBound::Min => write!(f, "timestamp before 4713 BC: {m} microseconds"),
+            TimestampBound::Max => write!(f, "timestamp after 294276 AD: {m} microseconds"),
+        }
+    }
+}
+
+impl std::error::Error for TimestampRangeError {}
+
+/// How timestamps out of the PostgreSQL range are handled.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RangePolicy {
+    /// Rejects the value.
+    #[default]
+    Error,
+
+    /// Converts the value to infinity/-infinity.
+    ClampToInfinity,
+}
+
+/// A timestamp value as stored by PostgreSQL.
+/// Ordered as -infinity < finite values < infinity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub(crate) enum PgInstant {
+    /// -infinity(i64::MIN)
+    NegInfinity,
+
+    /// Microseconds since 2000-01-01 00:00:00.
+    Micros(i128),
+
+    /// infinity(i64::MAX)
+    Infinity,
+}
+
+impl PgInstant {
+    /// Checks the range of a finite value.
+    pub(crate) fn check(self) -> Result<Self, TimestampRangeError> {
+        match self {
+            Self::Micros(m) if m < i128::from(MIN_TIMESTAMP_MICROS) => Err(TimestampRangeError {
+                micros: m,
+                bound: TimestampBound::Min,
+            }),
+            Self::Micros(m) if i128::from(END_TIMESTAMP_MICROS) <= m => Err(TimestampRangeError {
+                micros: m,
+                bound: TimestampBound::Max,
+            }),
+            _ => Ok(self),
+        }
+    }
+
+    /// Applies the [`RangePolicy`].
+    pub(crate) fn validate(self, policy: RangePolicy) -> Result<Self, TimestampRangeError> {
+        match (self.check(), policy) {
+            (Ok(s), _) => Ok(s),
+            (Err(e), RangePolicy::Error) => Err(e),
+            (Err(e), RangePolicy::ClampToInfinity) => match e.bound {
+                TimestampBound::Min => Ok(Self::NegInfinity),
+                TimestampBound::Max => Ok(Self::Infinity),
+            },
+        }
+    }
+
+    /// Converts to the i64 value sent to PostgreSQL.
+    pub(crate) fn to_i64(self) -> Result<i64, TimestampRangeError> {
+        match self.check()? {
+            Self::NegInfinity => Ok(i64::MIN),
+            Self::Infinity => Ok(i64::MAX),
+            // checked above
+            Self::Micros(m) => Ok(m as i64),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test_time {
+    mod pg_instant {
+        use crate::time::{PgInstant, RangePolicy, TimestampBound};
+        use crate::time::{END_TIMESTAMP_MICROS, MIN_TIMESTAMP_MICROS};
+
+        #[test]
+        fn min() {
+            let m: i128 = MIN_TIMESTAMP_MICROS.into();
+            assert_eq!(Ok(MIN_TIMESTAMP_MICROS), PgInstant::Micros(m).to_i64());
+            let e = PgInstant::Micros(m - 1).to_i64().unwrap_err();
+            assert_eq!(TimestampBound::Min, e.bound);
+            assert_eq!(m - 1, e.micros);
+        }
+
+        #[test]
+        fn max() {
+            let m: i128 = END_TIMESTAMP_MICROS.into();
+            assert_eq!(
+                Ok(END_TIMESTAMP_MICROS - 1),
+                PgInstant::Micros(m - 1).to_i64()
+            );
+            let e = PgInstant::Micros(m).to_i64().unwrap_err();
+            assert_eq!(TimestampBound::Max, e.bound);
+        }
+
+        #[test]
+        fn clamp() {
+            let lo = PgInstant::Micros(i128::from(MIN_TIMESTAMP_MICROS) - 1);
+            let hi = PgInstant::Micros(i128::from(END_TIMESTAMP_MICROS));
+            let p = RangePolicy::ClampToInfinity;
+            assert_eq!(Ok(PgInstant::NegInfinity), lo.validate(p));
+            assert_eq!(Ok(PgInstant::Infinity), hi.validate(p));
+            assert!(hi.validate(RangePolicy::Error).is_err());
+        }
+    }
+}
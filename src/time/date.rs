@@ -0,0 +1,127 @@
+//! Struct for DATE
+
+use serde::{Serialize, Serializer};
+
+use crate::registry::{type_hint, Raw};
+
+#[cfg(feature = "time_primitive")]
+use time::Date;
+
+/// The julian day of 2000-01-01.
+#[cfg(any(feature = "time_primitive", feature = "chrono"))]
+const PG_EPOCH_JDATE: i32 = 2_451_545;
+
+/// A date object for postgresql DATE(days since 2000-01-01)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct PgDate(i32);
+
+impl PgDate {
+    /// 'infinity'::date
+    pub const INFINITY: Self = Self(i32::MAX);
+
+    /// '-infinity'::date
+    pub const NEG_INFINITY: Self = Self(i32::MIN);
+
+    /// Creates a date from days since 2000-01-01.
+    pub fn from_days(days: i32) -> Self {
+        Self(days)
+    }
+
+    /// Days since 2000-01-01.
+    pub fn days(&self) -> i32 {
+        self.0
+    }
+}
+
+impl Serialize for PgDate {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ser.serialize_newtype_struct(type_hint!("date"), &Raw(&self.0.to_be_bytes()))
+    }
+}
+
+#[cfg(feature = "time_primitive")]
+impl From<Date> for PgDate {
+    fn from(d: Date) -> Self {
+        Self(d.to_julian_day() - PG_EPOCH_JDATE)
+    }
+}
+
+/// Converts from an ISO-8601 date(e.g, 2024-01-17)
+#[cfg(feature = "time_primitive")]
+impl TryFrom<&str> for PgDate {
+    type Error = time::error::Parse;
+
+    fn try_from(iso8601: &str) -> Result<Self, Self::Error> {
+        let fmt = time::macros::format_description!("[year]-[month]-[day]");
+        let d: Date = Date::parse(iso8601, fmt)?;
+        Ok(d.into())
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDate> for PgDate {
+    fn from(d: chrono::NaiveDate) -> Self {
+        use chrono::Datelike;
+        // 0001-01-01(CE day 1) is the julian day 1721426
+        Self(d.num_days_from_ce() + 1_721_425 - PG_EPOCH_JDATE)
+    }
+}
+
+#[cfg(test)]
+mod test_date {
+    use crate::item::to_writer;
+    use crate::time::date::PgDate;
+
+    #[test]
+    fn infinity() {
+        let mut buf: Vec<u8> = vec![];
+        to_writer(&mut buf, &PgDate::INFINITY).unwrap();
+        assert_eq!(vec![0, 0, 0, 4, 0x7f, 0xff, 0xff, 0xff], buf);
+    }
+
+    #[cfg(feature = "time_primitive")]
+    mod time_primitive {
+        use crate::item::to_writer;
+        use crate::time::date::PgDate;
+
+        #[test]
+        fn pg_epoch() {
+            let d: PgDate = PgDate::try_from("2000-01-01").unwrap();
+            assert_eq!(0, d.days());
+        }
+
+        #[test]
+        fn unix_epoch() {
+            let d: PgDate = time::macros::date!(1970 - 01 - 01).into();
+            assert_eq!(-10957, d.days());
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &d).unwrap();
+            let mut expected: Vec<u8> = vec![0, 0, 0, 4];
+            expected.extend_from_slice(&(-10957i32).to_be_bytes());
+            assert_eq!(expected, buf);
+        }
+
+        #[test]
+        fn invalid() {
+            assert!(PgDate::try_from("2024-02-30").is_err());
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    mod chrono_naive {
+        use chrono::NaiveDate;
+
+        use crate::time::date::PgDate;
+
+        #[test]
+        fn epochs() {
+            let p: PgDate = NaiveDate::from_ymd_opt(2000, 1, 1).unwrap().into();
+            assert_eq!(0, p.days());
+            let u: PgDate = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap().into();
+            assert_eq!(-10957, u.days());
+        }
+    }
+}
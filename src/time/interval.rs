@@ -0,0 +1,283 @@
+//! Struct for INTERVAL
+
+use core::time::Duration;
+
+use serde::{Serialize, Serializer};
+
+use crate::registry::{type_hint, Raw};
+
+/// An interval object for postgresql INTERVAL
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PgInterval {
+    micros: i64,
+    days: i32,
+    months: i32,
+}
+
+impl PgInterval {
+    /// Creates an interval from months, days and microseconds.
+    pub fn new(months: i32, days: i32, micros: i64) -> Self {
+        Self {
+            micros,
+            days,
+            months,
+        }
+    }
+
+    /// Months(a year is 12 months).
+    pub fn months(&self) -> i32 {
+        self.months
+    }
+
+    /// Days(a week is 7 days).
+    pub fn days(&self) -> i32 {
+        self.days
+    }
+
+    /// Microseconds.
+    pub fn micros(&self) -> i64 {
+        self.micros
+    }
+}
+
+impl Serialize for PgInterval {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut buf: [u8; 16] = [0; 16];
+        buf[..8].copy_from_slice(&self.micros.to_be_bytes());
+        buf[8..12].copy_from_slice(&self.days.to_be_bytes());
+        buf[12..].copy_from_slice(&self.months.to_be_bytes());
+        ser.serialize_newtype_struct(type_hint!("interval"), &Raw(&buf))
+    }
+}
+
+/// Converts to an interval of microseconds(sub-microseconds are truncated)
+impl TryFrom<Duration> for PgInterval {
+    type Error = String;
+
+    fn try_from(d: Duration) -> Result<Self, Self::Error> {
+        let micros: i64 = i64::try_from(d.as_micros())
+            .map_err(|_| format!("duration too large for an interval: {d:?}"))?;
+        Ok(Self::new(0, 0, micros))
+    }
+}
+
+/// Converts to an interval of microseconds(sub-microseconds are truncated)
+#[cfg(feature = "chrono")]
+impl TryFrom<chrono::TimeDelta> for PgInterval {
+    type Error = String;
+
+    fn try_from(d: chrono::TimeDelta) -> Result<Self, Self::Error> {
+        let micros: i64 = d
+            .num_microseconds()
+            .ok_or_else(|| format!("time delta too large for an interval: {d}"))?;
+        Ok(Self::new(0, 0, micros))
+    }
+}
+
+/// Converts the calendar(months, days) and time units of a span.
+///
+/// Sub-microseconds are truncated.
+#[cfg(feature = "jiff")]
+impl From<jiff::Span> for PgInterval {
+    fn from(s: jiff::Span) -> Self {
+        let months: i32 = i32::from(s.get_years()) * 12 + s.get_months();
+        let days: i32 = s.get_weeks() * 7 + s.get_days();
+        let micros: i64 = i64::from(s.get_hours()) * 3_600_000_000
+            + s.get_minutes() * 60_000_000
+            + s.get_seconds() * 1_000_000
+            + s.get_milliseconds() * 1000
+            + s.get_microseconds()
+            + s.get_nanoseconds() / 1000;
+        Self::new(months, days, micros)
+    }
+}
+
+/// Splits "1Y-2M" into (number, unit) pairs.
+fn components(s: &str) -> Result<Vec<(&str, char)>, String> {
+    let mut v: Vec<(&str, char)> = vec![];
+    let mut start: usize = 0;
+    for (i, c) in s.char_indices() {
+        if c.is_ascii_alphabetic() {
+            let num: &str = &s[start..i];
+            if num.is_empty() {
+                return Err(format!("missing a number before {c}"));
+            }
+            v.push((num, c));
+            start = i + 1;
+        }
+    }
+    match start == s.len() {
+        true => Ok(v),
+        false => Err(format!("missing a unit after {}", &s[start..])),
+    }
+}
+
+fn parse_int(num: &str) -> Result<i64, String> {
+    str::parse(num).map_err(|e| format!("invalid number {num}: {e}"))
+}
+
+/// Parses seconds with an optional fraction(e.g, -1.5) as microseconds.
+fn parse_seconds(num: &str) -> Result<i64, String> {
+    let (neg, abs) = match num.strip_prefix('-') {
+        Some(a) => (true, a),
+        None => (false, num),
+    };
+    if abs.starts_with(['+', '-']) {
+        return Err(format!("invalid seconds: {num}"));
+    }
+    let (int, frac) = abs.split_once(['.', ',']).unwrap_or((abs, ""));
+    let secs: i64 = parse_int(int)?;
+    let digits: String = frac.chars().chain("000000".chars()).take(6).collect();
+    let frac: i64 = match frac.chars().all(|c| c.is_ascii_digit()) {
+        true => parse_int(&digits)?,
+        false => return Err(format!("invalid fraction: {num}")),
+    };
+    let micros: i64 = secs
+        .checked_mul(1_000_000)
+        .and_then(|m| m.checked_add(frac))
+        .ok_or_else(|| format!("seconds out of range: {num}"))?;
+    Ok(if neg { -micros } else { micros })
+}
+
+/// Converts from an ISO-8601 duration(e.g, P1Y2M3DT4H5M6.5S, P2W)
+impl TryFrom<&str> for PgInterval {
+    type Error = String;
+
+    fn try_from(iso8601: &str) -> Result<Self, Self::Error> {
+        let body: &str = iso8601
+            .strip_prefix('P')
+            .ok_or_else(|| format!("not an ISO-8601 duration: {iso8601}"))?;
+        let (date, time) = match body.split_once('T') {
+            Some((d, t)) => (d, t),
+            None => (body, ""),
+        };
+        if body.is_empty() || body.ends_with('T') {
+            return Err(format!("no components in a duration: {iso8601}"));
+        }
+
+        let overflow = || format!("duration out of range: {iso8601}");
+        let mut months: i64 = 0;
+        let mut days: i64 = 0;
+        let mut micros: i64 = 0;
+        for (num, unit) in components(date)? {
+            let n: i64 = parse_int(num)?;
+            let (acc, mul) = match unit {
+                'Y' => (&mut months, 12),
+                'M' => (&mut months, 1),
+                'W' => (&mut days, 7),
+                'D' => (&mut days, 1),
+                _ => return Err(format!("unknown date unit: {unit}")),
+            };
+            *acc = n
+                .checked_mul(mul)
+                .and_then(|v| acc.checked_add(v))
+                .ok_or_else(overflow)?;
+        }
+        for (num, unit) in components(time)? {
+            let us: i64 = match unit {
+                'H' => parse_int(num)?.checked_mul(3_600_000_000),
+                'M' => parse_int(num)?.checked_mul(60_000_000),
+                'S' => Some(parse_seconds(num)?),
+                _ => return Err(format!("unknown time unit: {unit}")),
+            }
+            .ok_or_else(overflow)?;
+            micros = micros.checked_add(us).ok_or_else(overflow)?;
+        }
+
+        let months: i32 = i32::try_from(months).map_err(|_| overflow())?;
+        let days: i32 = i32::try_from(days).map_err(|_| overflow())?;
+        Ok(Self::new(months, days, micros))
+    }
+}
+
+#[cfg(test)]
+mod test_interval {
+    use core::time::Duration;
+
+    use crate::item::to_writer;
+    use crate::time::interval::PgInterval;
+
+    #[test]
+    fn serialize() {
+        let i: PgInterval = PgInterval::new(3, 2, 1);
+        let mut buf: Vec<u8> = vec![];
+        to_writer(&mut buf, &i).unwrap();
+        let expected: Vec<u8> = vec![0, 0, 0, 16, 0, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0, 2, 0, 0, 0, 3];
+        assert_eq!(expected, buf);
+    }
+
+    #[test]
+    fn duration() {
+        let i: PgInterval = PgInterval::try_from(Duration::from_millis(1500)).unwrap();
+        assert_eq!(PgInterval::new(0, 0, 1_500_000), i);
+        assert!(PgInterval::try_from(Duration::MAX).is_err());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn time_delta() {
+        let d: chrono::TimeDelta = chrono::TimeDelta::milliseconds(-1500);
+        let i: PgInterval = PgInterval::try_from(d).unwrap();
+        assert_eq!(PgInterval::new(0, 0, -1_500_000), i);
+    }
+
+    #[cfg(feature = "jiff")]
+    #[test]
+    fn span() {
+        use jiff::ToSpan;
+        let s: jiff::Span = 1.year().months(2).weeks(1).days(3).hours(4).milliseconds(5);
+        let i: PgInterval = s.into();
+        assert_eq!(PgInterval::new(14, 10, 4 * 3_600_000_000 + 5000), i);
+        let n: PgInterval = (-1).day().into();
+        assert_eq!(PgInterval::new(0, -1, 0), n);
+    }
+
+    #[test]
+    fn iso8601() {
+        let i: PgInterval = PgInterval::try_from("P1Y2M3DT4H5M6.5S").unwrap();
+        assert_eq!(14, i.months());
+        assert_eq!(3, i.days());
+        assert_eq!(((4 * 60 + 5) * 60 + 6) * 1_000_000 + 500_000, i.micros());
+    }
+
+    #[test]
+    fn iso8601_weeks() {
+        assert_eq!(
+            PgInterval::new(0, 14, 0),
+            PgInterval::try_from("P2W").unwrap()
+        );
+    }
+
+    #[test]
+    fn iso8601_negative() {
+        let i: PgInterval = PgInterval::try_from("P-1DT-0.25S").unwrap();
+        assert_eq!(PgInterval::new(0, -1, -250_000), i);
+        let i: PgInterval = PgInterval::try_from("PT-1S").unwrap();
+        assert_eq!(PgInterval::new(0, 0, -1_000_000), i);
+    }
+
+    #[test]
+    fn iso8601_double_sign() {
+        for s in ["PT--1S", "PT-+1S", "PT--0.5S"] {
+            assert!(PgInterval::try_from(s).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn iso8601_minutes() {
+        let i: PgInterval = PgInterval::try_from("P1MT1M").unwrap();
+        assert_eq!(PgInterval::new(1, 0, 60_000_000), i);
+    }
+
+    #[test]
+    fn iso8601_invalid() {
+        for s in [
+            "", "P", "PT", "1D", "PD", "P1", "P1H", "PT1D", "PT1.5M", "PT1.xS",
+        ] {
+            assert!(PgInterval::try_from(s).is_err(), "{s}");
+        }
+    }
+}
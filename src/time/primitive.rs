@@ -3,39 +3,93 @@
 use serde::ser::Error;
 use serde::{Serialize, Serializer};
 
-use postgres_types::{ToSql, Type};
+#[cfg(feature = "time_primitive")]
+use time::{Date, Duration, OffsetDateTime, PrimitiveDateTime, Time, UtcOffset};
 
-use bytes::BytesMut;
+use crate::registry::{type_hint, Raw};
+#[cfg(feature = "chrono")]
+use crate::time::PG_EPOCH_UNIX_MICROS;
+use crate::time::{PgInstant, RangePolicy, TimestampRangeError};
 
-use time::{Date, OffsetDateTime, PrimitiveDateTime, Time};
+/// 2000-01-01 00:00:00
+#[cfg(feature = "time_primitive")]
+const PG_EPOCH: PrimitiveDateTime = time::macros::datetime!(2000-01-01 0:00);
+
+/// How the UTC offset of an [`OffsetDateTime`] is handled.
+#[cfg(feature = "time_primitive")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OffsetPolicy {
+    /// Keeps the local wall clock time(the offset is dropped).
+    LocalWallClock,
+
+    /// Uses the wall clock time in UTC.
+    Utc,
+
+    /// Uses the wall clock time in the offset.
+    Fixed(UtcOffset),
+}
 
 /// A timestamp object for postgresql TIMESTAMP WITHOUT TIME ZONE
-#[derive(Debug)]
-pub struct Timestamp(PrimitiveDateTime);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Timestamp(PgInstant);
+
+impl Timestamp {
+    /// 'infinity'::timestamp
+    pub const INFINITY: Self = Self(PgInstant::Infinity);
+
+    /// '-infinity'::timestamp
+    pub const NEG_INFINITY: Self = Self(PgInstant::NegInfinity);
+
+    /// Returns false for infinity/-infinity.
+    pub fn is_finite(&self) -> bool {
+        matches!(self.0, PgInstant::Micros(_))
+    }
+
+    /// Checks if the value is in the range supported by PostgreSQL(4713 BC .. 294276 AD).
+    ///
+    /// Out of range values are rejected or converted to infinity/-infinity
+    /// following the policy.
+    pub fn validate(self, policy: RangePolicy) -> Result<Self, TimestampRangeError> {
+        self.0.validate(policy).map(Self)
+    }
+
+    /// Converts to a [`PrimitiveDateTime`](None for infinity/-infinity or unrepresentable values).
+    #[cfg(feature = "time_primitive")]
+    pub fn to_primitive(&self) -> Option<PrimitiveDateTime> {
+        match self.0 {
+            PgInstant::Micros(m) => {
+                let micros: i64 = i64::try_from(m).ok()?;
+                PG_EPOCH.checked_add(Duration::microseconds(micros))
+            }
+            _ => None,
+        }
+    }
+}
 
 impl Serialize for Timestamp {
     fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut buf = BytesMut::with_capacity(8);
-        let typ: Type = Type::TIMESTAMP;
-        let st: PrimitiveDateTime = self.0;
-        st.to_sql(&typ, &mut buf).map_err(|e| {
+        let i: i64 = self.0.to_i64().map_err(|e| {
             S::Error::custom(format!(
                 "unable to convert a primitive date time value: {e}"
             ))
         })?;
-        ser.serialize_bytes(&buf)
+        ser.serialize_newtype_struct(type_hint!("timestamp"), &Raw(&i.to_be_bytes()))
     }
 }
 
+#[cfg(feature = "time_primitive")]
 impl From<PrimitiveDateTime> for Timestamp {
     fn from(st: PrimitiveDateTime) -> Self {
-        Self(st)
+        let d: Duration = st - PG_EPOCH;
+        Self(PgInstant::Micros(d.whole_microseconds()))
     }
 }
 
+/// Keeps the local wall clock time(see [`OffsetPolicy::LocalWallClock`])
+#[cfg(feature = "time_primitive")]
 impl From<OffsetDateTime> for Timestamp {
     fn from(o: OffsetDateTime) -> Self {
         let d: Date = o.date();
@@ -45,19 +99,98 @@ impl From<OffsetDateTime> for Timestamp {
     }
 }
 
-/// Converts from a RFC3339 &str
+#[cfg(feature = "time_primitive")]
+impl Timestamp {
+    /// Converts an [`OffsetDateTime`] following the policy.
+    pub fn from_offset(o: OffsetDateTime, policy: OffsetPolicy) -> Self {
+        match policy {
+            OffsetPolicy::LocalWallClock => o.into(),
+            OffsetPolicy::Utc => o.to_offset(UtcOffset::UTC).into(),
+            OffsetPolicy::Fixed(f) => o.to_offset(f).into(),
+        }
+    }
+
+    /// Parses a RFC3339 timestamp or an ISO-8601 local timestamp.
+    ///
+    /// The policy is applied to RFC3339 timestamps only; local timestamps
+    /// (e.g, 2024-01-17T13:55:51.0, 2024-01-17 13:55:51) are used as is.
+    pub fn parse(s: &str, policy: OffsetPolicy) -> Result<Self, time::error::Parse> {
+        let fmt = &time::format_description::well_known::Rfc3339;
+        let local = time::macros::format_description!(
+            "[year]-[month]-[day][first [T][t][ ]][hour]:[minute]:[second][optional [.[subsecond]]]"
+        );
+        match OffsetDateTime::parse(s, fmt) {
+            Ok(o) => Ok(Self::from_offset(o, policy)),
+            Err(e) => PrimitiveDateTime::parse(s, local)
+                .map(Self::from)
+                .map_err(|_| e),
+        }
+    }
+}
+
+/// Converts from a RFC3339 &str(or an ISO-8601 local timestamp) keeping the wall clock time
+///
+/// See [`Timestamp::parse`] to normalize the offset.
+#[cfg(feature = "time_primitive")]
 impl TryFrom<&str> for Timestamp {
     type Error = time::error::Parse;
 
     fn try_from(rfc3339: &str) -> Result<Self, Self::Error> {
-        let fmt = &time::format_description::well_known::Rfc3339;
-        let o: OffsetDateTime = OffsetDateTime::parse(rfc3339, fmt)?;
-        Ok(o.into())
+        Self::parse(rfc3339, OffsetPolicy::LocalWallClock)
+    }
+}
+
+/// Converts the wall clock time(sub-microseconds are truncated)
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveDateTime> for Timestamp {
+    fn from(n: chrono::NaiveDateTime) -> Self {
+        let unix_micros: i64 = n.and_utc().timestamp_micros();
+        Self(PgInstant::Micros(
+            i128::from(unix_micros) - PG_EPOCH_UNIX_MICROS,
+        ))
+    }
+}
+
+/// Converts the wall clock time(sub-microseconds are truncated)
+#[cfg(feature = "jiff")]
+impl From<jiff::civil::DateTime> for Timestamp {
+    fn from(d: jiff::civil::DateTime) -> Self {
+        let epoch: jiff::civil::DateTime = jiff::civil::date(2000, 1, 1).at(0, 0, 0, 0);
+        Self(PgInstant::Micros(d.duration_since(epoch).as_micros()))
+    }
+}
+
+#[cfg(feature = "jiff")]
+impl Timestamp {
+    /// Converts the wall clock time of a [`jiff::Zoned`] in the time zone.
+    ///
+    /// Use `z.time_zone().clone()` to keep the wall clock time of the zoned value.
+    pub fn from_zoned(z: &jiff::Zoned, tz: jiff::tz::TimeZone) -> Self {
+        z.with_time_zone(tz).datetime().into()
+    }
+}
+
+/// Serializes a field as a [`Timestamp`] with `#[serde(with = "...")]`.
+///
+/// Any type convertible to a [`Timestamp`] can be used(e.g, `time::PrimitiveDateTime`).
+pub mod as_timestamp {
+    use serde::{Serialize, Serializer};
+
+    use super::Timestamp;
+
+    pub fn serialize<T, S>(val: &T, ser: S) -> Result<S::Ok, S::Error>
+    where
+        T: Clone + Into<Timestamp>,
+        S: Serializer,
+    {
+        let t: Timestamp = val.clone().into();
+        t.serialize(ser)
     }
 }
 
 #[cfg(test)]
 mod test_primitive {
+    #[cfg(feature = "time_primitive")]
     mod tztest {
         use std::time::SystemTime;
 
@@ -70,7 +203,7 @@ mod test_primitive {
             let e: SystemTime = SystemTime::UNIX_EPOCH;
             let o: OffsetDateTime = e.into();
             let t: Timestamp = o.into();
-            let p: PrimitiveDateTime = t.0;
+            let p: PrimitiveDateTime = t.to_primitive().unwrap();
             let (h, _, _) = p.as_hms();
             assert_eq!(0, h);
         }
@@ -82,9 +215,207 @@ mod test_primitive {
             let tk = time::macros::offset!(+09:00);
             let ot: OffsetDateTime = o.to_offset(tk);
             let t: Timestamp = ot.into();
-            let p: PrimitiveDateTime = t.0;
+            let p: PrimitiveDateTime = t.to_primitive().unwrap();
             let (h, _, _) = p.as_hms();
             assert_eq!(9, h);
         }
+
+        #[test]
+        fn infinity() {
+            assert_eq!(None, Timestamp::INFINITY.to_primitive());
+        }
+    }
+
+    #[cfg(feature = "time_primitive")]
+    mod as_timestamp {
+        use time::PrimitiveDateTime;
+
+        use crate::item::to_writer;
+
+        #[derive(serde::Serialize)]
+        struct Row {
+            #[serde(with = "crate::time::primitive::as_timestamp")]
+            created: PrimitiveDateTime,
+        }
+
+        #[test]
+        fn primitive() {
+            let p: PrimitiveDateTime = time::macros::datetime!(2000-01-01 0:00:00.000001);
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &Row { created: p }).unwrap();
+            assert_eq!(vec![0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0, 1], buf);
+        }
+    }
+
+    #[cfg(feature = "time_primitive")]
+    mod offset_policy {
+        use time::{OffsetDateTime, PrimitiveDateTime};
+
+        use crate::time::primitive::{OffsetPolicy, Timestamp};
+
+        fn tokyo_9am() -> OffsetDateTime {
+            time::macros::datetime!(2000-01-01 9:00 +09:00)
+        }
+
+        fn hour(t: Timestamp) -> u8 {
+            let p: PrimitiveDateTime = t.to_primitive().unwrap();
+            p.hour()
+        }
+
+        #[test]
+        fn local() {
+            let t = Timestamp::from_offset(tokyo_9am(), OffsetPolicy::LocalWallClock);
+            assert_eq!(9, hour(t));
+        }
+
+        #[test]
+        fn utc() {
+            let t = Timestamp::from_offset(tokyo_9am(), OffsetPolicy::Utc);
+            assert_eq!(0, hour(t));
+        }
+
+        #[test]
+        fn fixed() {
+            let ny = time::macros::offset!(-05:00);
+            let t = Timestamp::from_offset(tokyo_9am(), OffsetPolicy::Fixed(ny));
+            assert_eq!(19, hour(t));
+        }
+
+        #[test]
+        fn parse_rfc3339() {
+            let s: &str = "2000-01-01T09:00:00+09:00";
+            let t = Timestamp::parse(s, OffsetPolicy::Utc).unwrap();
+            assert_eq!(0, hour(t));
+            assert_eq!(9, hour(Timestamp::try_from(s).unwrap()));
+        }
+
+        #[test]
+        fn parse_local() {
+            let t = Timestamp::parse("2000-01-01T09:00:00.5", OffsetPolicy::Utc).unwrap();
+            assert_eq!(9, hour(t));
+            let p: PrimitiveDateTime = t.to_primitive().unwrap();
+            assert_eq!(500, p.millisecond());
+            let s = Timestamp::try_from("2000-01-01 09:00:00").unwrap();
+            assert_eq!(9, hour(s));
+        }
+
+        #[test]
+        fn parse_invalid() {
+            assert!(Timestamp::try_from("2000-01-01").is_err());
+            assert!(Timestamp::try_from("2000-01-01T25:00:00").is_err());
+        }
+    }
+
+    mod serialize {
+        use crate::item::to_writer;
+        use crate::time::primitive::Timestamp;
+
+        fn encode(t: Timestamp) -> Vec<u8> {
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &t).unwrap();
+            buf
+        }
+
+        #[cfg(feature = "time_primitive")]
+        #[test]
+        fn pg_epoch() {
+            let t: Timestamp = Timestamp::try_from("2000-01-01T00:00:00.000001Z").unwrap();
+            assert_eq!(vec![0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0, 1], encode(t));
+        }
+
+        #[test]
+        fn infinity() {
+            assert!(!Timestamp::INFINITY.is_finite());
+            let expected: Vec<u8> =
+                vec![0, 0, 0, 8, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff];
+            assert_eq!(expected, encode(Timestamp::INFINITY));
+        }
+
+        #[test]
+        fn neg_infinity() {
+            let expected: Vec<u8> = vec![0, 0, 0, 8, 0x80, 0, 0, 0, 0, 0, 0, 0];
+            assert_eq!(expected, encode(Timestamp::NEG_INFINITY));
+        }
+    }
+
+    #[cfg(feature = "time_primitive")]
+    mod validate {
+        use time::PrimitiveDateTime;
+
+        use crate::time::primitive::Timestamp;
+        use crate::time::{RangePolicy, TimestampBound};
+
+        #[test]
+        fn in_range() {
+            let p: PrimitiveDateTime = time::macros::datetime!(-4713-11-24 0:00);
+            let t: Timestamp = p.into();
+            assert_eq!(Ok(t), t.validate(RangePolicy::Error));
+        }
+
+        #[test]
+        fn error() {
+            let p: PrimitiveDateTime = time::macros::datetime!(-4713-11-23 23:59:59.999999);
+            let t: Timestamp = p.into();
+            let e = t.validate(RangePolicy::Error).unwrap_err();
+            assert_eq!(TimestampBound::Min, e.bound);
+        }
+
+        #[test]
+        fn clamp() {
+            let p: PrimitiveDateTime = time::macros::datetime!(-9999-01-01 0:00);
+            let t: Timestamp = p.into();
+            let c = t.validate(RangePolicy::ClampToInfinity);
+            assert_eq!(Ok(Timestamp::NEG_INFINITY), c);
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    mod chrono_naive {
+        use chrono::{NaiveDate, NaiveDateTime};
+
+        use crate::item::to_writer;
+        use crate::time::primitive::Timestamp;
+
+        #[test]
+        fn pg_epoch() {
+            let n: NaiveDateTime = NaiveDate::from_ymd_opt(2000, 1, 1)
+                .unwrap()
+                .and_hms_micro_opt(0, 0, 0, 1)
+                .unwrap();
+            let t: Timestamp = n.into();
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &t).unwrap();
+            assert_eq!(vec![0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0, 1], buf);
+        }
+    }
+
+    #[cfg(feature = "jiff")]
+    mod jiff_civil {
+        use jiff::tz::{offset, TimeZone};
+        use jiff::Zoned;
+
+        use crate::time::primitive::Timestamp;
+        use crate::time::PgInstant;
+
+        #[test]
+        fn pg_epoch() {
+            let t: Timestamp = jiff::civil::date(2000, 1, 1).at(0, 0, 0, 1000).into();
+            assert_eq!(PgInstant::Micros(1), t.0);
+            let u: Timestamp = jiff::civil::date(1970, 1, 1).at(0, 0, 0, 0).into();
+            assert_eq!(PgInstant::Micros(-946_684_800_000_000), u.0);
+        }
+
+        #[test]
+        fn zoned() {
+            let tokyo: TimeZone = TimeZone::fixed(offset(9));
+            let z: Zoned = jiff::civil::date(2000, 1, 1)
+                .at(9, 0, 0, 0)
+                .to_zoned(tokyo.clone())
+                .unwrap();
+            let utc: Timestamp = Timestamp::from_zoned(&z, TimeZone::UTC);
+            assert_eq!(PgInstant::Micros(0), utc.0);
+            let local: Timestamp = Timestamp::from_zoned(&z, tokyo);
+            assert_eq!(PgInstant::Micros(9 * 3_600_000_000), local.0);
+        }
     }
 }
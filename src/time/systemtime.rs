@@ -7,31 +7,96 @@ use std::time::SystemTime;
 use serde::ser::Error;
 use serde::{Serialize, Serializer};
 
-use postgres_types::{ToSql, Type};
-
-use bytes::BytesMut;
+use crate::registry::{type_hint, Raw};
+use crate::time::{PgInstant, RangePolicy, TimestampRangeError, PG_EPOCH_UNIX_MICROS};
 
 /// A timestamp object for postgresql TIMESTAMP WITH TIME ZONE
-#[derive(Debug)]
-pub struct Timestampz(SystemTime);
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Timestampz(PgInstant);
+
+impl Timestampz {
+    /// 'infinity'::timestamptz
+    pub const INFINITY: Self = Self(PgInstant::Infinity);
+
+    /// '-infinity'::timestamptz
+    pub const NEG_INFINITY: Self = Self(PgInstant::NegInfinity);
+
+    /// Returns false for infinity/-infinity.
+    pub fn is_finite(&self) -> bool {
+        matches!(self.0, PgInstant::Micros(_))
+    }
+
+    /// Checks if the value is in the range supported by PostgreSQL(4713 BC .. 294276 AD).
+    ///
+    /// Out of range values are rejected or converted to infinity/-infinity
+    /// following the policy.
+    pub fn validate(self, policy: RangePolicy) -> Result<Self, TimestampRangeError> {
+        self.0.validate(policy).map(Self)
+    }
+}
 
 impl Serialize for Timestampz {
     fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
     where
         S: Serializer,
     {
-        let mut buf = BytesMut::with_capacity(8);
-        let typ: Type = Type::TIMESTAMPTZ;
-        let st: SystemTime = self.0;
-        st.to_sql(&typ, &mut buf)
+        let i: i64 = self
+            .0
+            .to_i64()
             .map_err(|e| S::Error::custom(format!("unable to convert a system time value: {e}")))?;
-        ser.serialize_bytes(&buf)
+        ser.serialize_newtype_struct(type_hint!("timestamptz"), &Raw(&i.to_be_bytes()))
     }
 }
 
 impl From<SystemTime> for Timestampz {
     fn from(st: SystemTime) -> Self {
-        Self(st)
+        let unix_micros: i128 = match st.duration_since(SystemTime::UNIX_EPOCH) {
+            Ok(d) => d.as_micros() as i128,
+            Err(e) => -(e.duration().as_micros() as i128),
+        };
+        Self(PgInstant::Micros(unix_micros - PG_EPOCH_UNIX_MICROS))
+    }
+}
+
+/// Converts the instant(sub-microseconds are truncated)
+#[cfg(feature = "chrono")]
+impl<Tz> From<chrono::DateTime<Tz>> for Timestampz
+where
+    Tz: chrono::TimeZone,
+{
+    fn from(d: chrono::DateTime<Tz>) -> Self {
+        let unix_micros: i64 = d.timestamp_micros();
+        Self(PgInstant::Micros(
+            i128::from(unix_micros) - PG_EPOCH_UNIX_MICROS,
+        ))
+    }
+}
+
+/// Converts the instant(sub-microseconds are truncated)
+#[cfg(feature = "jiff")]
+impl From<jiff::Timestamp> for Timestampz {
+    fn from(t: jiff::Timestamp) -> Self {
+        let unix_micros: i64 = t.as_microsecond();
+        Self(PgInstant::Micros(
+            i128::from(unix_micros) - PG_EPOCH_UNIX_MICROS,
+        ))
+    }
+}
+
+/// Converts the instant of a zoned value(the time zone is not needed)
+#[cfg(feature = "jiff")]
+impl From<jiff::Zoned> for Timestampz {
+    fn from(z: jiff::Zoned) -> Self {
+        z.timestamp().into()
+    }
+}
+
+/// Converts the instant(sub-microseconds are truncated)
+#[cfg(feature = "time_primitive")]
+impl From<time::OffsetDateTime> for Timestampz {
+    fn from(o: time::OffsetDateTime) -> Self {
+        let unix_micros: i128 = o.unix_timestamp_nanos() / 1000;
+        Self(PgInstant::Micros(unix_micros - PG_EPOCH_UNIX_MICROS))
     }
 }
 
@@ -44,3 +109,204 @@ impl TryFrom<Duration> for Timestampz {
             .ok_or_else(|| String::from("invalid duration"))
     }
 }
+
+/// Serializes a field as a [`Timestampz`] with `#[serde(with = "...")]`.
+///
+/// Any type convertible to a [`Timestampz`] can be used(e.g, [`SystemTime`]).
+///
+/// ```
+/// use std::time::SystemTime;
+///
+/// #[derive(serde::Serialize)]
+/// struct Row {
+///     #[serde(with = "row2pgcopy::time::systemtime::as_timestamptz")]
+///     created: SystemTime,
+/// }
+/// ```
+pub mod as_timestamptz {
+    use serde::{Serialize, Serializer};
+
+    use super::Timestampz;
+
+    pub fn serialize<T, S>(val: &T, ser: S) -> Result<S::Ok, S::Error>
+    where
+        T: Clone + Into<Timestampz>,
+        S: Serializer,
+    {
+        let t: Timestampz = val.clone().into();
+        t.serialize(ser)
+    }
+}
+
+#[cfg(test)]
+mod test_systemtime {
+    mod serialize {
+        use core::time::Duration;
+
+        use std::time::SystemTime;
+
+        use crate::item::to_writer;
+        use crate::time::systemtime::Timestampz;
+
+        fn encode(t: Timestampz) -> Vec<u8> {
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &t).unwrap();
+            buf
+        }
+
+        #[test]
+        fn pg_epoch() {
+            let t: Timestampz = Timestampz::try_from(Duration::from_secs(946684800)).unwrap();
+            assert_eq!(vec![0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0, 0], encode(t));
+        }
+
+        #[test]
+        fn before_pg_epoch() {
+            let st: SystemTime = SystemTime::UNIX_EPOCH + Duration::from_secs(946684799);
+            let t: Timestampz = st.into();
+            let i: i64 = -1_000_000;
+            let mut expected: Vec<u8> = vec![0, 0, 0, 8];
+            expected.extend_from_slice(&i.to_be_bytes());
+            assert_eq!(expected, encode(t));
+        }
+
+        #[test]
+        fn infinity() {
+            assert!(!Timestampz::INFINITY.is_finite());
+            let expected: Vec<u8> =
+                vec![0, 0, 0, 8, 0x7f, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff];
+            assert_eq!(expected, encode(Timestampz::INFINITY));
+        }
+
+        #[test]
+        fn neg_infinity() {
+            let expected: Vec<u8> = vec![0, 0, 0, 8, 0x80, 0, 0, 0, 0, 0, 0, 0];
+            assert_eq!(expected, encode(Timestampz::NEG_INFINITY));
+        }
+    }
+
+    mod validate {
+        use core::time::Duration;
+
+        use std::time::SystemTime;
+
+        use crate::item::to_writer;
+        use crate::time::systemtime::Timestampz;
+        use crate::time::{RangePolicy, TimestampBound};
+
+        fn far_future() -> Timestampz {
+            let d: Duration = Duration::from_secs(300_000 * 366 * 86400);
+            let st: SystemTime = SystemTime::UNIX_EPOCH.checked_add(d).unwrap();
+            st.into()
+        }
+
+        #[test]
+        fn in_range() {
+            let t: Timestampz = SystemTime::UNIX_EPOCH.into();
+            assert_eq!(Ok(t), t.validate(RangePolicy::Error));
+        }
+
+        #[test]
+        fn error() {
+            let e = far_future().validate(RangePolicy::Error).unwrap_err();
+            assert_eq!(TimestampBound::Max, e.bound);
+        }
+
+        #[test]
+        fn clamp() {
+            let t = far_future().validate(RangePolicy::ClampToInfinity);
+            assert_eq!(Ok(Timestampz::INFINITY), t);
+        }
+
+        #[test]
+        fn serialize() {
+            let mut buf: Vec<u8> = vec![];
+            let e = to_writer(&mut buf, &far_future()).unwrap_err();
+            assert!(e.to_string().contains("after 294276 AD"));
+        }
+    }
+
+    mod as_timestamptz {
+        use std::time::SystemTime;
+
+        use crate::item::to_writer;
+        use crate::time::systemtime::Timestampz;
+
+        #[derive(serde::Serialize)]
+        struct Row {
+            #[serde(with = "crate::time::systemtime::as_timestamptz")]
+            created: SystemTime,
+        }
+
+        #[test]
+        fn system_time() {
+            let st: SystemTime = SystemTime::UNIX_EPOCH;
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &Row { created: st }).unwrap();
+            let mut expected: Vec<u8> = vec![];
+            to_writer(&mut expected, &Timestampz::from(st)).unwrap();
+            assert_eq!(12, buf.len());
+            assert_eq!(expected, buf);
+        }
+
+        #[cfg(feature = "time_primitive")]
+        #[test]
+        fn offset_date_time() {
+            #[derive(serde::Serialize)]
+            struct Row {
+                #[serde(with = "crate::time::systemtime::as_timestamptz")]
+                created: time::OffsetDateTime,
+            }
+            let o = time::macros::datetime!(2000-01-01 9:00 +09:00);
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &Row { created: o }).unwrap();
+            assert_eq!(vec![0, 0, 0, 8, 0, 0, 0, 0, 0, 0, 0, 0], buf);
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    mod chrono_datetime {
+        use chrono::{DateTime, FixedOffset, Utc};
+
+        use crate::time::systemtime::Timestampz;
+
+        #[test]
+        fn same_instant() {
+            let u: DateTime<Utc> = DateTime::from_timestamp(946684800, 0).unwrap();
+            let o: DateTime<FixedOffset> =
+                DateTime::parse_from_rfc3339("2000-01-01T09:00:00+09:00").unwrap();
+            let tu: Timestampz = u.into();
+            let to: Timestampz = o.into();
+            assert_eq!(tu, to);
+            let e: Timestampz =
+                Timestampz::try_from(core::time::Duration::from_secs(946684800)).unwrap();
+            assert_eq!(e, tu);
+        }
+    }
+
+    #[cfg(feature = "jiff")]
+    mod jiff_timestamp {
+        use jiff::tz::{offset, TimeZone};
+        use jiff::Zoned;
+
+        use crate::time::systemtime::Timestampz;
+        use crate::time::PgInstant;
+
+        #[test]
+        fn pg_epoch() {
+            let t: jiff::Timestamp = jiff::Timestamp::from_second(946684800).unwrap();
+            let tz: Timestampz = t.into();
+            assert_eq!(PgInstant::Micros(0), tz.0);
+        }
+
+        #[test]
+        fn zoned() {
+            let z: Zoned = jiff::civil::date(2000, 1, 1)
+                .at(9, 0, 0, 0)
+                .to_zoned(TimeZone::fixed(offset(9)))
+                .unwrap();
+            let tz: Timestampz = z.into();
+            assert_eq!(PgInstant::Micros(0), tz.0);
+        }
+    }
+}
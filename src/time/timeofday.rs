@@ -0,0 +1,270 @@
+//! Structs for TIME and TIME WITH TIME ZONE
+
+use serde::{Serialize, Serializer};
+
+use crate::registry::{type_hint, Raw};
+
+#[cfg(feature = "time_primitive")]
+use time::{Time, UtcOffset};
+
+/// Microseconds in a day(24:00:00 is a valid time).
+const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+/// The maximum UTC offset of a TIME WITH TIME ZONE in seconds(15:59:59).
+pub const MAX_OFFSET_SECONDS: i32 = 57_599;
+
+/// A time object for postgresql TIME(microseconds since midnight)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PgTime(i64);
+
+impl PgTime {
+    /// Creates a time from microseconds since midnight(0 ..= 24:00:00).
+    pub fn from_micros(micros: i64) -> Option<Self> {
+        (0..=MICROS_PER_DAY)
+            .contains(&micros)
+            .then_some(Self(micros))
+    }
+
+    /// Microseconds since midnight.
+    pub fn micros(&self) -> i64 {
+        self.0
+    }
+}
+
+impl Serialize for PgTime {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        ser.serialize_newtype_struct(type_hint!("time"), &Raw(&self.0.to_be_bytes()))
+    }
+}
+
+#[cfg(feature = "time_primitive")]
+impl From<Time> for PgTime {
+    fn from(t: Time) -> Self {
+        let (h, m, s, us) = t.as_hms_micro();
+        let secs: i64 = i64::from(h) * 3600 + i64::from(m) * 60 + i64::from(s);
+        Self(secs * 1_000_000 + i64::from(us))
+    }
+}
+
+/// Converts from an ISO-8601 time(e.g, 13:55:51.5)
+#[cfg(feature = "time_primitive")]
+impl TryFrom<&str> for PgTime {
+    type Error = time::error::Parse;
+
+    fn try_from(iso8601: &str) -> Result<Self, Self::Error> {
+        let fmt =
+            time::macros::format_description!("[hour]:[minute]:[second][optional [.[subsecond]]]");
+        let t: Time = Time::parse(iso8601, fmt)?;
+        Ok(t.into())
+    }
+}
+
+/// Converts the time(sub-microseconds are truncated, leap seconds are folded)
+#[cfg(feature = "chrono")]
+impl From<chrono::NaiveTime> for PgTime {
+    fn from(t: chrono::NaiveTime) -> Self {
+        use chrono::Timelike;
+        let secs: i64 = t.num_seconds_from_midnight().into();
+        let us: i64 = i64::from(t.nanosecond() % 1_000_000_000) / 1000;
+        Self(secs * 1_000_000 + us)
+    }
+}
+
+/// A time object for postgresql TIME WITH TIME ZONE
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PgTimeTz {
+    time: PgTime,
+
+    /// Seconds east of UTC(e.g, +09:00 -> 32400).
+    offset: i32,
+}
+
+impl PgTimeTz {
+    /// Creates a time with a UTC offset in seconds(east of UTC is positive).
+    ///
+    /// Returns None if the offset is beyond ±15:59:59([`MAX_OFFSET_SECONDS`]).
+    pub fn new(time: PgTime, offset_seconds: i32) -> Option<Self> {
+        (-MAX_OFFSET_SECONDS..=MAX_OFFSET_SECONDS)
+            .contains(&offset_seconds)
+            .then_some(Self {
+                time,
+                offset: offset_seconds,
+            })
+    }
+
+    /// The local time.
+    pub fn time(&self) -> PgTime {
+        self.time
+    }
+
+    /// The UTC offset in seconds(east of UTC is positive).
+    pub fn offset_seconds(&self) -> i32 {
+        self.offset
+    }
+}
+
+impl Serialize for PgTimeTz {
+    /// Writes the time and the zone(seconds west of UTC as postgresql does).
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut buf: [u8; 12] = [0; 12];
+        buf[..8].copy_from_slice(&self.time.0.to_be_bytes());
+        buf[8..].copy_from_slice(&(-self.offset).to_be_bytes());
+        ser.serialize_newtype_struct(type_hint!("timetz"), &Raw(&buf))
+    }
+}
+
+/// Converts the time and the offset(rejects offsets beyond ±15:59:59).
+#[cfg(feature = "time_primitive")]
+impl TryFrom<(Time, UtcOffset)> for PgTimeTz {
+    type Error = String;
+
+    fn try_from(tz: (Time, UtcOffset)) -> Result<Self, Self::Error> {
+        let (t, o) = tz;
+        with_offset(t.into(), o.whole_seconds())
+    }
+}
+
+/// Converts from an ISO-8601 time with an offset(e.g, 13:55:51.5+09:00, 04:55:51Z)
+#[cfg(feature = "time_primitive")]
+impl TryFrom<&str> for PgTimeTz {
+    type Error = String;
+
+    fn try_from(iso8601: &str) -> Result<Self, Self::Error> {
+        let pos: usize = iso8601.rfind(['+', '-', 'Z']).unwrap_or(iso8601.len());
+        let (t, o) = iso8601.split_at(pos);
+        let t: PgTime = PgTime::try_from(t).map_err(|e| format!("invalid time: {e}"))?;
+        let o: UtcOffset = match o {
+            "Z" => UtcOffset::UTC,
+            _ => UtcOffset::parse(
+                o,
+                time::macros::format_description!(
+                    "[offset_hour sign:mandatory][optional [:[offset_minute]]]"
+                ),
+            )
+            .map_err(|e| format!("invalid offset: {e}"))?,
+        };
+        with_offset(t, o.whole_seconds())
+    }
+}
+
+/// Converts the time and the offset(rejects offsets beyond ±15:59:59).
+#[cfg(feature = "chrono")]
+impl TryFrom<(chrono::NaiveTime, chrono::FixedOffset)> for PgTimeTz {
+    type Error = String;
+
+    fn try_from(tz: (chrono::NaiveTime, chrono::FixedOffset)) -> Result<Self, Self::Error> {
+        let (t, o) = tz;
+        with_offset(t.into(), o.local_minus_utc())
+    }
+}
+
+#[cfg(any(feature = "time_primitive", feature = "chrono"))]
+fn with_offset(time: PgTime, offset_seconds: i32) -> Result<PgTimeTz, String> {
+    PgTimeTz::new(time, offset_seconds)
+        .ok_or_else(|| format!("offset out of range: {offset_seconds} seconds"))
+}
+
+#[cfg(test)]
+mod test_timeofday {
+    use crate::item::to_writer;
+    use crate::time::timeofday::{PgTime, PgTimeTz};
+
+    #[test]
+    fn from_micros() {
+        assert!(PgTime::from_micros(-1).is_none());
+        assert!(PgTime::from_micros(86_400_000_000).is_some());
+        assert!(PgTime::from_micros(86_400_000_001).is_none());
+    }
+
+    #[test]
+    fn timetz() {
+        let t: PgTime = PgTime::from_micros(1).unwrap();
+        let tz: PgTimeTz = PgTimeTz::new(t, 32400).unwrap();
+        let mut buf: Vec<u8> = vec![];
+        to_writer(&mut buf, &tz).unwrap();
+        let mut expected: Vec<u8> = vec![0, 0, 0, 12, 0, 0, 0, 0, 0, 0, 0, 1];
+        expected.extend_from_slice(&(-32400i32).to_be_bytes());
+        assert_eq!(expected, buf);
+    }
+
+    #[test]
+    fn timetz_offset() {
+        let t: PgTime = PgTime::from_micros(0).unwrap();
+        assert!(PgTimeTz::new(t, 57_599).is_some());
+        assert!(PgTimeTz::new(t, -57_599).is_some());
+        assert!(PgTimeTz::new(t, 57_600).is_none());
+        assert!(PgTimeTz::new(t, 100_000).is_none());
+        assert!(PgTimeTz::new(t, i32::MIN).is_none());
+    }
+
+    #[cfg(feature = "time_primitive")]
+    mod time_primitive {
+        use crate::time::timeofday::{PgTime, PgTimeTz};
+
+        #[test]
+        fn time() {
+            let t: PgTime = PgTime::try_from("13:55:51.5").unwrap();
+            assert_eq!(((13 * 60 + 55) * 60 + 51) * 1_000_000 + 500_000, t.micros());
+        }
+
+        #[test]
+        fn time_without_fraction() {
+            let t: PgTime = time::macros::time!(0:00:01).into();
+            assert_eq!(1_000_000, t.micros());
+            assert_eq!(t, PgTime::try_from("00:00:01").unwrap());
+        }
+
+        #[test]
+        fn timetz() {
+            let tz: PgTimeTz = PgTimeTz::try_from("13:55:51+09:00").unwrap();
+            assert_eq!(32400, tz.offset_seconds());
+            let tz: PgTimeTz = PgTimeTz::try_from("13:55:51.25-05:30").unwrap();
+            assert_eq!(-19800, tz.offset_seconds());
+            let tz: PgTimeTz = PgTimeTz::try_from("04:55:51Z").unwrap();
+            assert_eq!(0, tz.offset_seconds());
+        }
+
+        #[test]
+        fn timetz_invalid() {
+            assert!(PgTimeTz::try_from("13:55:51").is_err());
+            assert!(PgTimeTz::try_from("25:00:00Z").is_err());
+            assert!(PgTimeTz::try_from("13:55:51+16:00").is_err());
+        }
+
+        #[test]
+        fn timetz_offset() {
+            let o = time::UtcOffset::from_hms(20, 0, 0).unwrap();
+            assert!(PgTimeTz::try_from((time::macros::time!(0:00), o)).is_err());
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    mod chrono_naive {
+        use chrono::{FixedOffset, NaiveTime};
+
+        use crate::time::timeofday::{PgTime, PgTimeTz};
+
+        #[test]
+        fn time() {
+            let n: NaiveTime = NaiveTime::from_hms_micro_opt(0, 0, 1, 5).unwrap();
+            let t: PgTime = n.into();
+            assert_eq!(1_000_005, t.micros());
+        }
+
+        #[test]
+        fn timetz() {
+            let n: NaiveTime = NaiveTime::from_hms_opt(13, 55, 51).unwrap();
+            let o: FixedOffset = FixedOffset::east_opt(32400).unwrap();
+            let tz: PgTimeTz = (n, o).try_into().unwrap();
+            assert_eq!(32400, tz.offset_seconds());
+            let o: FixedOffset = FixedOffset::east_opt(-72000).unwrap();
+            assert!(PgTimeTz::try_from((n, o)).is_err());
+        }
+    }
+}
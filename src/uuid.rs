@@ -2,7 +2,10 @@
 
 use serde::{Serialize, Serializer};
 
-#[derive(Debug)]
+use crate::item::PgNum;
+use crate::registry::{type_hint, Raw};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Uuid(pub u128);
 
 impl Serialize for Uuid {
@@ -12,7 +15,7 @@ impl Serialize for Uuid {
     {
         let b: [u8; 16] = self.0.to_be_bytes();
         let s: &[u8] = &b;
-        ser.serialize_bytes(s)
+        ser.serialize_newtype_struct(type_hint!("uuid"), &Raw(s))
     }
 }
 
@@ -21,3 +24,193 @@ impl From<u128> for Uuid {
         Self(u)
     }
 }
+
+/// Enables uuid[] columns(e.g, [`crate::item::PgNumArray<Uuid>`]).
+impl PgNum for Uuid {
+    fn to_buf(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.0.to_be_bytes());
+    }
+
+    fn type_num() -> u32 {
+        0x0b86
+    }
+
+    fn size() -> usize {
+        16
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<::uuid::Uuid> for Uuid {
+    fn from(u: ::uuid::Uuid) -> Self {
+        Self(u.as_u128())
+    }
+}
+
+#[cfg(feature = "uuid")]
+impl From<Uuid> for ::uuid::Uuid {
+    fn from(u: Uuid) -> Self {
+        Self::from_u128(u.0)
+    }
+}
+
+/// Parses a hyphenated, simple, braced or urn uuid
+/// (e.g, "urn:uuid:cafef00d-dead-beaf-face-864299792458").
+#[cfg(feature = "uuid")]
+impl TryFrom<&str> for Uuid {
+    type Error = ::uuid::Error;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        ::uuid::Uuid::try_parse(s).map(Self::from)
+    }
+}
+
+/// Enables uuid[] columns(e.g, [`crate::item::PgNumArray<uuid::Uuid>`]).
+///
+/// A `uuid::Uuid` field is serialized as a uuid column as is
+/// (see [`crate::config::Config::with_human_readable`]), but a `Vec<uuid::Uuid>`
+/// is rejected: its elements are plain bytes that cannot be told from a bytea.
+#[cfg(feature = "uuid")]
+impl PgNum for ::uuid::Uuid {
+    fn to_buf(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(self.as_bytes());
+    }
+
+    fn type_num() -> u32 {
+        Uuid::type_num()
+    }
+
+    fn size() -> usize {
+        Uuid::size()
+    }
+}
+
+/// Serializes a u128 field as a [`Uuid`] with `#[serde(with = "...")]`.
+///
+/// ```
+/// #[derive(serde::Serialize)]
+/// struct Row {
+///     #[serde(with = "row2pgcopy::uuid::as_uuid")]
+///     id: u128,
+/// }
+/// ```
+pub mod as_uuid {
+    use serde::{Serialize, Serializer};
+
+    use super::Uuid;
+
+    pub fn serialize<S>(val: &u128, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        Uuid(*val).serialize(ser)
+    }
+}
+
+#[cfg(test)]
+mod test_uuid {
+    mod as_uuid {
+        use crate::item::to_writer;
+
+        #[derive(serde::Serialize)]
+        struct Row {
+            #[serde(with = "crate::uuid::as_uuid")]
+            id: u128,
+        }
+
+        #[test]
+        fn u128() {
+            let r = Row {
+                id: 0xcafef00d_dead_beaf_face_864299792458,
+            };
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &r).unwrap();
+            let mut expected: Vec<u8> = vec![0, 0, 0, 16];
+            expected.extend_from_slice(&r.id.to_be_bytes());
+            assert_eq!(expected, buf);
+        }
+    }
+
+    mod array {
+        use crate::item::{to_writer, PgNumArray};
+        use crate::uuid::Uuid;
+
+        #[test]
+        fn uuids() {
+            let a: PgNumArray<Uuid> = PgNumArray(vec![Uuid(1), Uuid(2)]);
+            let mut buf: Vec<u8> = vec![];
+            to_writer(&mut buf, &a).unwrap();
+            let mut expected: Vec<u8> = vec![0, 0, 0, 60];
+            expected.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0x0b, 0x86]);
+            expected.extend_from_slice(&[0, 0, 0, 2, 0, 0, 0, 1]);
+            expected.extend_from_slice(&[0, 0, 0, 16]);
+            expected.extend_from_slice(&1u128.to_be_bytes());
+            expected.extend_from_slice(&[0, 0, 0, 16]);
+            expected.extend_from_slice(&2u128.to_be_bytes());
+            assert_eq!(expected, buf);
+        }
+    }
+
+    #[cfg(feature = "uuid")]
+    mod uuid_crate {
+        use crate::item::{to_writer, Error, PgNumArray};
+        use crate::uuid::Uuid;
+
+        const HYPHENATED: &str = "cafef00d-dead-beaf-face-864299792458";
+        const RAW: u128 = 0xcafef00d_dead_beaf_face_864299792458;
+
+        #[test]
+        fn from() {
+            let u: ::uuid::Uuid = ::uuid::Uuid::from_u128(RAW);
+            let w: Uuid = u.into();
+            assert_eq!(Uuid(RAW), w);
+            let b: ::uuid::Uuid = w.into();
+            assert_eq!(u, b);
+        }
+
+        #[test]
+        fn parse() {
+            let forms: [String; 4] = [
+                HYPHENATED.into(),
+                HYPHENATED.replace('-', ""),
+                format!("{{{HYPHENATED}}}"),
+                format!("urn:uuid:{HYPHENATED}"),
+            ];
+            for f in forms {
+                assert_eq!(Uuid(RAW), Uuid::try_from(f.as_str()).unwrap(), "{f}");
+            }
+            assert!(Uuid::try_from("cafef00d").is_err());
+        }
+
+        #[test]
+        fn array() {
+            let w: PgNumArray<Uuid> = PgNumArray(vec![Uuid(RAW)]);
+            let u: PgNumArray<::uuid::Uuid> = PgNumArray(vec![::uuid::Uuid::from_u128(RAW)]);
+            let mut bw: Vec<u8> = vec![];
+            let mut bu: Vec<u8> = vec![];
+            to_writer(&mut bw, &w).unwrap();
+            to_writer(&mut bu, &u).unwrap();
+            assert_eq!(bw, bu);
+        }
+
+        #[test]
+        fn vec() {
+            #[derive(serde::Serialize)]
+            struct Row {
+                ids: Vec<::uuid::Uuid>,
+            }
+
+            let r = Row {
+                ids: vec![::uuid::Uuid::from_u128(RAW)],
+            };
+            let mut buf: Vec<u8> = vec![];
+            match to_writer(&mut buf, &r) {
+                Err(Error::Message(m)) => {
+                    assert!(m.contains("ids"), "{m}");
+                    assert!(m.contains("PgNumArray"), "{m}");
+                }
+                r => panic!("unexpected result: {r:?}"),
+            }
+        }
+    }
+}
@@ -0,0 +1,305 @@
+//! pgvector structs(vector, halfvec, sparsevec).
+//!
+//! The OIDs of extension types are database-specific; register them to use
+//! vectors in composite values or arrays(see [`crate::registry::TypeRegistry`]).
+
+use serde::ser::Error;
+use serde::{Serialize, Serializer};
+
+use crate::registry::{type_hint, Raw};
+
+/// The maximum number of dimensions of a vector/halfvec.
+pub const VECTOR_MAX_DIM: usize = 16_000;
+
+/// The maximum number of dimensions of a sparsevec.
+pub const SPARSEVEC_MAX_DIM: u32 = 1_000_000_000;
+
+/// The maximum number of non-zero elements of a sparsevec.
+pub const SPARSEVEC_MAX_NNZ: usize = 16_000;
+
+/// Checks the number of dimensions of a vector/halfvec.
+fn check_dim(dim: usize) -> Result<i16, String> {
+    match dim {
+        1..=VECTOR_MAX_DIM => Ok(dim as i16),
+        _ => Err(format!(
+            "vector must have 1 to {VECTOR_MAX_DIM} dimensions: {dim}"
+        )),
+    }
+}
+
+/// Checks the number of dimensions against a declared size(e.g, vector(3)).
+fn check_declared(dim: usize, declared: usize) -> Result<(), String> {
+    match dim == declared {
+        true => Ok(()),
+        false => Err(format!("expected {declared} dimensions, not {dim}")),
+    }
+}
+
+/// A vector(single precision floats)
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PgVector(pub Vec<f32>);
+
+impl PgVector {
+    /// Creates a vector checking the number of dimensions.
+    pub fn with_dim(v: Vec<f32>, dim: usize) -> Result<Self, String> {
+        check_declared(v.len(), dim)?;
+        Ok(Self(v))
+    }
+
+    /// Encodes the dimensions, an unused i16 and the elements.
+    fn to_buf(&self) -> Result<Vec<u8>, String> {
+        let dim: i16 = check_dim(self.0.len())?;
+        let mut buf: Vec<u8> = Vec::with_capacity(4 + 4 * self.0.len());
+        buf.extend_from_slice(&dim.to_be_bytes());
+        buf.extend_from_slice(&0i16.to_be_bytes());
+        for f in &self.0 {
+            if !f.is_finite() {
+                return Err(format!("vector cannot contain {f}"));
+            }
+            buf.extend_from_slice(&f.to_be_bytes());
+        }
+        Ok(buf)
+    }
+}
+
+impl From<Vec<f32>> for PgVector {
+    fn from(v: Vec<f32>) -> Self {
+        Self(v)
+    }
+}
+
+impl From<&[f32]> for PgVector {
+    fn from(s: &[f32]) -> Self {
+        Self(s.into())
+    }
+}
+
+impl Serialize for PgVector {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let buf: Vec<u8> = self.to_buf().map_err(S::Error::custom)?;
+        ser.serialize_newtype_struct(type_hint!("vector"), &Raw(&buf))
+    }
+}
+
+/// A halfvec(half precision floats)
+#[cfg(feature = "half")]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PgHalfVec(pub Vec<half::f16>);
+
+#[cfg(feature = "half")]
+impl PgHalfVec {
+    /// Creates a halfvec checking the number of dimensions.
+    pub fn with_dim(v: Vec<half::f16>, dim: usize) -> Result<Self, String> {
+        check_declared(v.len(), dim)?;
+        Ok(Self(v))
+    }
+
+    /// Encodes the dimensions, an unused i16 and the elements.
+    fn to_buf(&self) -> Result<Vec<u8>, String> {
+        let dim: i16 = check_dim(self.0.len())?;
+        let mut buf: Vec<u8> = Vec::with_capacity(4 + 2 * self.0.len());
+        buf.extend_from_slice(&dim.to_be_bytes());
+        buf.extend_from_slice(&0i16.to_be_bytes());
+        for f in &self.0 {
+            if !f.is_finite() {
+                return Err(format!("halfvec cannot contain {f}"));
+            }
+            buf.extend_from_slice(&f.to_bits().to_be_bytes());
+        }
+        Ok(buf)
+    }
+}
+
+#[cfg(feature = "half")]
+impl From<Vec<half::f16>> for PgHalfVec {
+    fn from(v: Vec<half::f16>) -> Self {
+        Self(v)
+    }
+}
+
+/// Converts to half precision(values out of range become infinite).
+#[cfg(feature = "half")]
+impl From<&[f32]> for PgHalfVec {
+    fn from(s: &[f32]) -> Self {
+        Self(s.iter().map(|f| half::f16::from_f32(*f)).collect())
+    }
+}
+
+#[cfg(feature = "half")]
+impl Serialize for PgHalfVec {
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let buf: Vec<u8> = self.to_buf().map_err(S::Error::custom)?;
+        ser.serialize_newtype_struct(type_hint!("halfvec"), &Raw(&buf))
+    }
+}
+
+/// A sparsevec(non-zero elements and their 0-based indices)
+#[derive(Debug, Clone, PartialEq)]
+pub struct PgSparseVec {
+    dim: u32,
+    indices: Vec<u32>,
+    values: Vec<f32>,
+}
+
+impl PgSparseVec {
+    /// Creates a sparsevec.
+    ///
+    /// The indices must be ascending and less than the dimensions, and the
+    /// values must be finite and non-zero.
+    pub fn new(dim: u32, indices: Vec<u32>, values: Vec<f32>) -> Result<Self, String> {
+        if !(1..=SPARSEVEC_MAX_DIM).contains(&dim) {
+            return Err(format!(
+                "sparsevec must have 1 to {SPARSEVEC_MAX_DIM} dimensions: {dim}"
+            ));
+        }
+        if indices.len() != values.len() {
+            return Err(format!(
+                "{} indices for {} values",
+                indices.len(),
+                values.len()
+            ));
+        }
+        if SPARSEVEC_MAX_NNZ < values.len() {
+            return Err(format!(
+                "sparsevec cannot have more than {SPARSEVEC_MAX_NNZ} non-zero elements"
+            ));
+        }
+        if let Some(i) = indices.iter().find(|i| dim <= **i) {
+            return Err(format!("index out of bounds: {i}(dimensions: {dim})"));
+        }
+        if indices.windows(2).any(|w| w[1] <= w[0]) {
+            return Err("indices must be ascending".into());
+        }
+        if let Some(f) = values.iter().find(|f| !f.is_finite() || 0.0 == **f) {
+            return Err(format!("sparsevec cannot contain {f}"));
+        }
+        Ok(Self {
+            dim,
+            indices,
+            values,
+        })
+    }
+
+    /// Creates a sparsevec from the elements of a dense vector(zeros are dropped).
+    pub fn from_dense(dense: &[f32]) -> Result<Self, String> {
+        let dim: u32 = u32::try_from(dense.len())
+            .map_err(|_| format!("too many dimensions: {}", dense.len()))?;
+        let (indices, values): (Vec<u32>, Vec<f32>) = dense
+            .iter()
+            .enumerate()
+            .filter(|(_, f)| 0.0 != **f)
+            .map(|(i, f)| (i as u32, *f))
+            .unzip();
+        Self::new(dim, indices, values)
+    }
+
+    pub fn dim(&self) -> u32 {
+        self.dim
+    }
+
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+
+    pub fn values(&self) -> &[f32] {
+        &self.values
+    }
+}
+
+impl Serialize for PgSparseVec {
+    /// Writes the dimensions, the number of non-zero elements, an unused i32,
+    /// the indices and the values.
+    fn serialize<S>(&self, ser: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        // checked by new(): dim <= 1e9, nnz <= 16000
+        let nnz: i32 = self.values.len() as i32;
+        let mut buf: Vec<u8> = Vec::with_capacity(12 + 8 * self.values.len());
+        buf.extend_from_slice(&(self.dim as i32).to_be_bytes());
+        buf.extend_from_slice(&nnz.to_be_bytes());
+        buf.extend_from_slice(&0i32.to_be_bytes());
+        for i in &self.indices {
+            buf.extend_from_slice(&(*i as i32).to_be_bytes());
+        }
+        for f in &self.values {
+            buf.extend_from_slice(&f.to_be_bytes());
+        }
+        ser.serialize_newtype_struct(type_hint!("sparsevec"), &Raw(&buf))
+    }
+}
+
+#[cfg(test)]
+mod test_vector {
+    use crate::item::to_writer;
+    use crate::vector::{PgSparseVec, PgVector};
+
+    #[test]
+    fn vector() {
+        let v: PgVector = PgVector::from(vec![1.0, -0.5]);
+        let mut buf: Vec<u8> = vec![];
+        to_writer(&mut buf, &v).unwrap();
+        let mut expected: Vec<u8> = vec![0, 0, 0, 12, 0, 2, 0, 0];
+        expected.extend_from_slice(&1.0f32.to_be_bytes());
+        expected.extend_from_slice(&(-0.5f32).to_be_bytes());
+        assert_eq!(expected, buf);
+    }
+
+    #[test]
+    fn vector_invalid() {
+        let mut buf: Vec<u8> = vec![];
+        assert!(to_writer(&mut buf, &PgVector(vec![])).is_err());
+        assert!(to_writer(&mut buf, &PgVector(vec![f32::NAN])).is_err());
+        assert!(to_writer(&mut buf, &PgVector(vec![0.0; 16_001])).is_err());
+        assert!(buf.is_empty());
+    }
+
+    #[test]
+    fn declared() {
+        assert!(PgVector::with_dim(vec![1.0, 2.0, 3.0], 3).is_ok());
+        let e = PgVector::with_dim(vec![1.0, 2.0], 3).unwrap_err();
+        assert_eq!("expected 3 dimensions, not 2", e);
+    }
+
+    #[cfg(feature = "half")]
+    #[test]
+    fn halfvec() {
+        use crate::vector::PgHalfVec;
+
+        let v: PgHalfVec = PgHalfVec::from(&[1.0f32, 0.5][..]);
+        let mut buf: Vec<u8> = vec![];
+        to_writer(&mut buf, &v).unwrap();
+        assert_eq!(vec![0, 0, 0, 8, 0, 2, 0, 0, 0x3c, 0, 0x38, 0], buf);
+        assert!(to_writer(&mut buf, &PgHalfVec::from(&[1e6f32][..])).is_err());
+    }
+
+    #[test]
+    fn sparsevec() {
+        let v = PgSparseVec::from_dense(&[0.0, 1.0, 0.0, 2.0, 0.0]).unwrap();
+        assert_eq!(5, v.dim());
+        assert_eq!(&[1, 3], v.indices());
+        let mut buf: Vec<u8> = vec![];
+        to_writer(&mut buf, &v).unwrap();
+        let mut expected: Vec<u8> = vec![0, 0, 0, 28, 0, 0, 0, 5, 0, 0, 0, 2, 0, 0, 0, 0];
+        expected.extend_from_slice(&[0, 0, 0, 1, 0, 0, 0, 3]);
+        expected.extend_from_slice(&1.0f32.to_be_bytes());
+        expected.extend_from_slice(&2.0f32.to_be_bytes());
+        assert_eq!(expected, buf);
+    }
+
+    #[test]
+    fn sparsevec_invalid() {
+        assert!(PgSparseVec::new(0, vec![], vec![]).is_err());
+        assert!(PgSparseVec::new(3, vec![3], vec![1.0]).is_err());
+        assert!(PgSparseVec::new(3, vec![1, 0], vec![1.0, 2.0]).is_err());
+        assert!(PgSparseVec::new(3, vec![0], vec![0.0]).is_err());
+        assert!(PgSparseVec::new(3, vec![0], vec![]).is_err());
+        assert!(PgSparseVec::new(3, vec![0, 2], vec![1.0, 2.0]).is_ok());
+    }
+}